@@ -41,13 +41,6 @@ android {
                 "proguard-rules.pro"
             )
         }
-        debug {
-            externalNativeBuild {
-                cmake {
-                    targets("acubenchtest")
-                }
-            }
-        }
     }
     compileOptions {
         sourceCompatibility = JavaVersion.VERSION_11
@@ -39,13 +39,13 @@ class AcubenchTest {
         val time = measureTime {
             val cpuReport = acubench.cpu()
             assert(cpuReport.cryptoTps > 0)
-            assert(cpuReport.mathTps > 0)
-            assert(cpuReport.sortTps > 0)
+            assert(cpuReport.mathGops > 0)
+            assert(cpuReport.sortEps > 0)
 
             val cpuMultithreadReport = acubench.cpuMultithread()
             assert(cpuMultithreadReport.cryptoTps > 0)
-            assert(cpuMultithreadReport.mathTps > 0)
-            assert(cpuMultithreadReport.sortTps > 0)
+            assert(cpuMultithreadReport.mathGops > 0)
+            assert(cpuMultithreadReport.sortEps > 0)
 
             val ramReport = acubench.ram()
             assert(ramReport.totalMemory > 0)
@@ -75,8 +75,8 @@ class AcubenchTest {
             val report = acubench.cpu(Acubench.CpuConfig(duration = duration))
 
             assert(report.cryptoTps > 0)
-            assert(report.mathTps > 0)
-            assert(report.sortTps > 0)
+            assert(report.mathGops > 0)
+            assert(report.sortEps > 0)
         }
 
         assert(time <= duration + 1.seconds)
@@ -89,13 +89,48 @@ class AcubenchTest {
             val report = acubench.cpuMultithread(Acubench.CpuConfig(duration = duration))
 
             assert(report.cryptoTps > 0)
-            assert(report.mathTps > 0)
-            assert(report.sortTps > 0)
+            assert(report.mathGops > 0)
+            assert(report.sortEps > 0)
         }
 
         assert(time <= duration + 1.seconds)
     }
 
+    @Test
+    fun testCpuMathElementType() {
+        for (elementType in listOf(
+            Acubench.CpuConfig.MATH_ELEMENT_TYPE_I8,
+            Acubench.CpuConfig.MATH_ELEMENT_TYPE_I16,
+            Acubench.CpuConfig.MATH_ELEMENT_TYPE_F32,
+            Acubench.CpuConfig.MATH_ELEMENT_TYPE_F64,
+        )) {
+            val config = Acubench.CpuConfig(mathElementType = elementType)
+
+            for (report in listOf(acubench.cpu(config), acubench.cpuMultithread(config))) {
+                assert(report.mathGops > 0)
+                assert(report.mathElementType == elementType)
+            }
+        }
+    }
+
+    @Test
+    fun testCpuSuites() {
+        val config = Acubench.CpuConfig(
+            zk = true,
+            bigint = true,
+            inference = true,
+        )
+
+        for (report in listOf(acubench.cpu(config), acubench.cpuMultithread(config))) {
+            assert(report.zkNttTps > 0)
+            assert(report.zkMsmTps > 0)
+            assert(report.bigintMulTps > 0)
+            assert(report.bigintModexpTps > 0)
+            assert(report.inferenceI8Tps > 0)
+            assert(report.inferenceF32Tps > 0)
+        }
+    }
+
     @Test
     fun testRam() {
         val report = acubench.ram()
@@ -107,6 +142,40 @@ class AcubenchTest {
         assert(report.accessConcurrentAvgTime > 0)
     }
 
+    @Test
+    fun testRamSuites() {
+        val report = acubench.ram(
+            Acubench.RamConfig(
+                latency = true,
+                sweep = true,
+                stream = true,
+                accessScaling = true,
+                malloc = true,
+                mmap = true,
+                probe = true,
+                integrity = true,
+                coherency = true,
+            )
+        )
+
+        assert(report.latencyAvgNs > 0)
+        assert(report.sweepDramLatencyNs > 0)
+        assert(report.sweepPeakGbs > 0)
+        assert(report.streamTriadGbs > 0)
+        assert(report.streamTriadMultiGbs > 0)
+        assert(report.accessScalingSingleGbs > 0)
+        assert(report.accessScalingMultiGbs > 0)
+        assert(report.mallocAps > 0)
+        assert(report.mallocMultiAps > 0)
+        assert(report.mmapFirstTouchFps > 0)
+        assert(report.mmapFirstTouchGibTime > 0)
+        assert(report.probeMaxLen > 0)
+        assert(report.probeStatus != Acubench.RamReport.PROBE_STATUS_NOT_RUN)
+        assert(report.integrityGbs > 0)
+        assert(report.coherencySharedOps > 0)
+        assert(report.coherencyPaddedOps > 0)
+    }
+
     @Test
     fun testStorage() {
         val report = acubench.storage(context)
@@ -27,16 +27,44 @@ Java_com_acurast_bench_Acubench__1_1new_1_1(JNIEnv *env, jobject thiz, jlong tot
     #ifdef __aarch64__
         uint64_t hwcap = getauxval(AT_HWCAP);
         uint64_t hwcap2 = getauxval(AT_HWCAP2);
+        auto dotprod_mask = TypedU64 {.t = 0, .v = HWCAP_ASIMDDP};
         auto sve_mask = TypedU64 {.t = 0, .v = HWCAP_SVE};
         auto i8mm_mask = TypedU64 {.t = 1, .v = HWCAP2_I8MM};
+        auto aes_mask = TypedU64 {.t = 0, .v = HWCAP_AES};
+        auto pmull_mask = TypedU64 {.t = 0, .v = HWCAP_PMULL};
+        auto sha1_mask = TypedU64 {.t = 0, .v = HWCAP_SHA1};
+        auto sha2_mask = TypedU64 {.t = 0, .v = HWCAP_SHA2};
+        auto sha3_mask = TypedU64 {.t = 0, .v = HWCAP_SHA3};
+        auto sha512_mask = TypedU64 {.t = 0, .v = HWCAP_SHA512};
     #else
         uint64_t hwcap = 0;
         uint64_t hwcap2 = 0;
+        auto dotprod_mask = TypedU64 {.t = 0, .v = 0};
         auto sve_mask = TypedU64 {.t = 0, .v = 0};
         auto i8mm_mask = TypedU64 {.t = 0, .v = 0};
+        auto aes_mask = TypedU64 {.t = 0, .v = 0};
+        auto pmull_mask = TypedU64 {.t = 0, .v = 0};
+        auto sha1_mask = TypedU64 {.t = 0, .v = 0};
+        auto sha2_mask = TypedU64 {.t = 0, .v = 0};
+        auto sha3_mask = TypedU64 {.t = 0, .v = 0};
+        auto sha512_mask = TypedU64 {.t = 0, .v = 0};
     #endif //__aarch64__
 
-    auto bench = new_bench(total_ram, avail_storage, hwcap, hwcap2, sve_mask, i8mm_mask);
+    auto bench = new_bench(
+        total_ram,
+        avail_storage,
+        hwcap,
+        hwcap2,
+        dotprod_mask,
+        sve_mask,
+        i8mm_mask,
+        aes_mask,
+        pmull_mask,
+        sha1_mask,
+        sha2_mask,
+        sha3_mask,
+        sha512_mask
+    );
 
     return reinterpret_cast<jlong>(bench);
 }
@@ -49,21 +77,33 @@ Java_com_acurast_bench_Acubench__1_1delete_1_1(JNIEnv *env, jobject thiz, jlong
 
 jobject jcpu_report(JNIEnv *env, CpuReport *report) {
     jclass clazz = env->FindClass("com/acurast/bench/Acubench$CpuReport");
-    jmethodID init = env->GetMethodID(clazz, "<init>", "(DDD)V");
-
-    return env->NewObject(clazz, init, report->crypto_tps, report->math_tps, report->sort_tps);
+    jmethodID init = env->GetMethodID(clazz, "<init>", "(DDDDZJDDDDIDD)V");
+
+    return env->NewObject(clazz, init, report->crypto_tps, report->math_gops, report->sort_eps,
+                          report->crypto_soft_tps, (jboolean) report->crypto_accelerated,
+                          (jlong) report->features, report->zk_ntt_tps, report->zk_msm_tps,
+                          report->bigint_mul_tps, report->bigint_modexp_tps,
+                          (jint) report->math_element_type, report->inference_i8_tps,
+                          report->inference_f32_tps);
 }
 
 extern "C"
 JNIEXPORT jobject JNICALL
 Java_com_acurast_bench_Acubench__1_1cpu_1_1(JNIEnv *env, jobject thiz, jlong ptr, jlong duration,
-                                        jlong enc_data_len, jlong math_data_len, jlong sort_data_len) {
+                                        jlong enc_data_len, jlong math_data_len, jlong sort_data_len,
+                                        jboolean crypto_soft_baseline, jboolean zk, jboolean bigint,
+                                        jint math_element_type, jboolean inference) {
 
     auto report = bench_cpu((void *) ptr, CpuConfig{
         .duration = (size_t) duration,
         .enc_data_len = (size_t) enc_data_len,
         .math_data_len = (size_t) math_data_len,
-        .sort_data_len = (size_t) sort_data_len
+        .sort_data_len = (size_t) sort_data_len,
+        .crypto_soft_baseline = (bool) crypto_soft_baseline,
+        .zk = (bool) zk,
+        .bigint = (bool) bigint,
+        .math_element_type = (uint8_t) math_element_type,
+        .inference = (bool) inference
     });
     auto jreport = jcpu_report(env, report);
     if (report->err != nullptr && report->err_len != 0) {
@@ -78,12 +118,20 @@ extern "C"
 JNIEXPORT jobject JNICALL
 Java_com_acurast_bench_Acubench__1_1cpu_1multithread_1_1(JNIEnv *env, jobject thiz, jlong ptr,
                                                          jlong duration, jlong enc_data_len,
-                                                         jlong math_data_len, jlong sort_data_len) {
+                                                         jlong math_data_len, jlong sort_data_len,
+                                                         jboolean crypto_soft_baseline, jboolean zk,
+                                                         jboolean bigint, jint math_element_type,
+                                                         jboolean inference) {
     auto report = bench_cpu_multithread((void *) ptr, CpuConfig{
         .duration = (size_t) duration,
         .enc_data_len = (size_t) enc_data_len,
         .math_data_len = (size_t) math_data_len,
-        .sort_data_len = (size_t) sort_data_len
+        .sort_data_len = (size_t) sort_data_len,
+        .crypto_soft_baseline = (bool) crypto_soft_baseline,
+        .zk = (bool) zk,
+        .bigint = (bool) bigint,
+        .math_element_type = (uint8_t) math_element_type,
+        .inference = (bool) inference
     });
 
     auto jreport = jcpu_report(env, report);
@@ -97,19 +145,42 @@ Java_com_acurast_bench_Acubench__1_1cpu_1multithread_1_1(JNIEnv *env, jobject th
 
 jobject jram_report(JNIEnv *env, RamReport *report) {
     jclass clazz = env->FindClass("com/acurast/bench/Acubench$RamReport");
-    jmethodID init = env->GetMethodID(clazz, "<init>", "(JDDDD)V");
-
-    return env->NewObject(clazz, init, (jlong) report->total_mem, report->alloc_avg_t, report->access_seq_avg_t, report->access_rand_avg_t, report->access_con_avg_t);
+    jmethodID init = env->GetMethodID(clazz, "<init>", "(JDDDDDJDDDDDDDDJDDJDIDDDD)V");
+
+    return env->NewObject(clazz, init, (jlong) report->total_mem, report->alloc_avg_t, report->access_seq_avg_t, report->access_rand_avg_t, report->access_con_avg_t,
+                          report->latency_avg_ns, (jlong) report->sweep_llc_size,
+                          report->sweep_dram_latency_ns, report->sweep_peak_gbs,
+                          report->stream_triad_gbs, report->stream_triad_multi_gbs,
+                          report->access_scaling_single_gbs, report->access_scaling_multi_gbs,
+                          report->malloc_aps, report->malloc_multi_aps,
+                          (jlong) report->malloc_peak_rss_growth, report->mmap_first_touch_fps,
+                          report->mmap_first_touch_gib_t, (jlong) report->probe_max_len,
+                          report->probe_gbs, (jint) report->probe_status, report->integrity_gbs,
+                          report->coherency_shared_ops, report->coherency_padded_ops,
+                          report->coherency_avg_latency_ns);
 }
 
 extern "C"
 JNIEXPORT jobject JNICALL
 Java_com_acurast_bench_Acubench__1_1ram_1_1(JNIEnv *env, jobject thiz, jlong ptr, jlong alloc_data_len,
-                                            jlong access_data_len, jlong iters) {
+                                            jlong access_data_len, jlong iters, jboolean latency,
+                                            jboolean sweep, jboolean stream,
+                                            jboolean access_scaling, jboolean malloc,
+                                            jboolean mmap, jboolean probe, jboolean integrity,
+                                            jboolean coherency) {
     auto report = bench_ram((void *) ptr, RamConfig{
         .alloc_data_len = (size_t) alloc_data_len,
         .access_data_len = (size_t) access_data_len,
-        .iters = (size_t) iters
+        .iters = (size_t) iters,
+        .latency = (bool) latency,
+        .sweep = (bool) sweep,
+        .stream = (bool) stream,
+        .access_scaling = (bool) access_scaling,
+        .malloc = (bool) malloc,
+        .mmap = (bool) mmap,
+        .probe = (bool) probe,
+        .integrity = (bool) integrity,
+        .coherency = (bool) coherency
     });
 
     auto jreport = jram_report(env, report);
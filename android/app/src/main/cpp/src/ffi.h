@@ -23,8 +23,15 @@ extern "C" {
         uint64_t avail_storage,
         uint64_t hwcap,
         uint64_t hwcap2,
+        TypedU64 dotprod_mask,
         TypedU64 sve_mask,
-        TypedU64 i8mm_mask
+        TypedU64 i8mm_mask,
+        TypedU64 aes_mask,
+        TypedU64 pmull_mask,
+        TypedU64 sha1_mask,
+        TypedU64 sha2_mask,
+        TypedU64 sha3_mask,
+        TypedU64 sha512_mask
     );
 
     void drop_bench(void *bench);
@@ -34,12 +41,49 @@ extern "C" {
         size_t enc_data_len;
         size_t math_data_len;
         size_t sort_data_len;
+        bool crypto_soft_baseline;
+        bool zk;
+        bool bigint;
+        uint8_t math_element_type;
+        bool inference;
+    };
+
+    enum CpuFeature : uint64_t {
+        FEATURE_NEON = 1 << 0,
+        FEATURE_DOTPROD = 1 << 1,
+        FEATURE_SVE = 1 << 2,
+        FEATURE_I8MM = 1 << 3,
+        FEATURE_SSE41 = 1 << 4,
+        FEATURE_AVX2 = 1 << 5,
+        FEATURE_AES = 1 << 6,
+        FEATURE_PMULL = 1 << 7,
+        FEATURE_SHA1 = 1 << 8,
+        FEATURE_SHA2 = 1 << 9,
+        FEATURE_SHA3 = 1 << 10,
+        FEATURE_SHA512 = 1 << 11,
+    };
+
+    enum MathElementType : uint8_t {
+        MATH_ELEMENT_TYPE_I8 = 0,
+        MATH_ELEMENT_TYPE_I16 = 1,
+        MATH_ELEMENT_TYPE_F32 = 2,
+        MATH_ELEMENT_TYPE_F64 = 3,
     };
 
     struct CpuReport {
         double crypto_tps;
-        double math_tps;
-        double sort_tps;
+        double math_gops;
+        double sort_eps;
+        double crypto_soft_tps;
+        uint64_t features;
+        bool crypto_accelerated;
+        double zk_ntt_tps;
+        double zk_msm_tps;
+        double bigint_mul_tps;
+        double bigint_modexp_tps;
+        uint8_t math_element_type;
+        double inference_i8_tps;
+        double inference_f32_tps;
         const char *err;
         size_t err_len;
     };
@@ -52,6 +96,24 @@ extern "C" {
         size_t alloc_data_len;
         size_t access_data_len;
         size_t iters;
+        bool latency;
+        bool sweep;
+        bool stream;
+        bool access_scaling;
+        bool malloc;
+        bool mmap;
+        bool probe;
+        bool integrity;
+        bool coherency;
+    };
+
+    enum ProbeStatus : uint8_t {
+        PROBE_STATUS_NOT_RUN = 0,
+        PROBE_STATUS_LIMIT = 1,
+        PROBE_STATUS_MARGIN = 2,
+        PROBE_STATUS_REFUSED = 3,
+        PROBE_STATUS_KILLED = 4,
+        PROBE_STATUS_FAILED = 5,
     };
 
     struct RamReport {
@@ -60,6 +122,26 @@ extern "C" {
         double access_seq_avg_t;
         double access_rand_avg_t;
         double access_con_avg_t;
+        double latency_avg_ns;
+        uint64_t sweep_llc_size;
+        double sweep_dram_latency_ns;
+        double sweep_peak_gbs;
+        double stream_triad_gbs;
+        double stream_triad_multi_gbs;
+        double access_scaling_single_gbs;
+        double access_scaling_multi_gbs;
+        double malloc_aps;
+        double malloc_multi_aps;
+        uint64_t malloc_peak_rss_growth;
+        double mmap_first_touch_fps;
+        double mmap_first_touch_gib_t;
+        uint64_t probe_max_len;
+        double probe_gbs;
+        uint8_t probe_status;
+        double integrity_gbs;
+        double coherency_shared_ops;
+        double coherency_padded_ops;
+        double coherency_avg_latency_ns;
         const char *err;
         size_t err_len;
     };
@@ -85,21 +167,6 @@ struct StorageReport {
 StorageReport* bench_storage(void *bench, StorageConfig config);
 void drop_storage_report(void *report);
 
-    /******** C++ -> Rust ********/
-
-    struct Ops {
-        uint64_t ok;
-        uint64_t err;
-    };
-
-    Ops matrix_mul_sve_i8mm(
-            const int8_t *matrix_a,
-            const int8_t *matrix_b,
-            int32_t *matrix_r,
-            size_t n,
-            size_t timeout_timestamp
-    );
-
 #ifdef __cplusplus
 };
 #endif //__cplusplus
@@ -1,8 +0,0 @@
-//
-// Created by Julia on 29.01.2025.
-//
-
-#ifndef ACUBENCH_ACUBENCH_TEST_JNI_H
-#define ACUBENCH_ACUBENCH_TEST_JNI_H
-
-#endif //ACUBENCH_ACUBENCH_TEST_JNI_H
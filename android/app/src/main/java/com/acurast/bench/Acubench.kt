@@ -16,13 +16,13 @@ public class Acubench(context: Context) {
     }
 
     public fun cpu(config: CpuConfig = CpuConfig()): CpuReport =
-        __cpu__(ptr, config.duration.inWholeMilliseconds, config.encodingDataSize, config.mathDataSize, config.sortDataSize)
+        __cpu__(ptr, config.duration.inWholeMilliseconds, config.encodingDataSize, config.mathDataSize, config.sortDataSize, config.cryptoSoftBaseline, config.zk, config.bigint, config.mathElementType, config.inference)
 
     public fun cpuMultithread(config: CpuConfig = CpuConfig()): CpuReport =
-        __cpu_multithread__(ptr, config.duration.inWholeMilliseconds, config.encodingDataSize, config.mathDataSize, config.sortDataSize)
+        __cpu_multithread__(ptr, config.duration.inWholeMilliseconds, config.encodingDataSize, config.mathDataSize, config.sortDataSize, config.cryptoSoftBaseline, config.zk, config.bigint, config.mathElementType, config.inference)
 
     public fun ram(config: RamConfig = RamConfig()): RamReport =
-        __ram__(ptr, config.allocDataSize, config.accessDataSize, config.iters)
+        __ram__(ptr, config.allocDataSize, config.accessDataSize, config.iters, config.latency, config.sweep, config.stream, config.accessScaling, config.malloc, config.mmap, config.probe, config.integrity, config.coherency)
 
     public fun storage(config: StorageConfig): StorageReport =
         __storage__(ptr, config.dir.absolutePath.toByteArray(charset = Charsets.UTF_8), config.accessDataSizeMB, config.iters)
@@ -50,10 +50,10 @@ public class Acubench(context: Context) {
     private external fun __new__(totalRam: Long, availStorage: Long): Long
     private external fun __delete__(ptr: Long)
 
-    private external fun __cpu__(ptr: Long, duration: Long, encDataLen: Long, mathDataLen: Long, sortDataLen: Long): CpuReport
-    private external fun __cpu_multithread__(ptr: Long, duration: Long, encDataLen: Long, mathDataLen: Long, sortDataLen: Long): CpuReport
+    private external fun __cpu__(ptr: Long, duration: Long, encDataLen: Long, mathDataLen: Long, sortDataLen: Long, cryptoSoftBaseline: Boolean, zk: Boolean, bigint: Boolean, mathElementType: Int, inference: Boolean): CpuReport
+    private external fun __cpu_multithread__(ptr: Long, duration: Long, encDataLen: Long, mathDataLen: Long, sortDataLen: Long, cryptoSoftBaseline: Boolean, zk: Boolean, bigint: Boolean, mathElementType: Int, inference: Boolean): CpuReport
 
-    private external fun __ram__(ptr: Long, allocDataLen: Long, accessDataLen: Long, iters: Long): RamReport
+    private external fun __ram__(ptr: Long, allocDataLen: Long, accessDataLen: Long, iters: Long, latency: Boolean, sweep: Boolean, stream: Boolean, accessScaling: Boolean, malloc: Boolean, mmap: Boolean, probe: Boolean, integrity: Boolean, coherency: Boolean): RamReport
 
     private external fun __storage__(ptr: Long, dir: ByteArray, accessDataLenMB: Long, iters: Long): StorageReport
 
@@ -62,8 +62,22 @@ public class Acubench(context: Context) {
         val encodingDataSize: Long = ENCODING_DATA_SIZE_DEFAULT,
         val mathDataSize: Long = MATH_DATA_SIZE_DEFAULT,
         val sortDataSize: Long = SORT_DATA_SIZE_DEFAULT,
+        val cryptoSoftBaseline: Boolean = false,
+        /** Also runs the NTT and MSM workloads. */
+        val zk: Boolean = false,
+        /** Also runs the 2048-bit multiplication and modular exponentiation. */
+        val bigint: Boolean = false,
+        /** One of the `MATH_ELEMENT_TYPE_*` values. */
+        val mathElementType: Int = MATH_ELEMENT_TYPE_F32,
+        /** Also runs the quantized and f32 neural network inference. */
+        val inference: Boolean = false,
     ) {
         public companion object {
+            public const val MATH_ELEMENT_TYPE_I8: Int = 0
+            public const val MATH_ELEMENT_TYPE_I16: Int = 1
+            public const val MATH_ELEMENT_TYPE_F32: Int = 2
+            public const val MATH_ELEMENT_TYPE_F64: Int = 3
+
             private val DURATION_DEFAULT = 3.seconds
             private const val ENCODING_DATA_SIZE_DEFAULT = 10 * KB
             private const val MATH_DATA_SIZE_DEFAULT = 200L
@@ -73,16 +87,70 @@ public class Acubench(context: Context) {
 
     public data class CpuReport(
         val cryptoTps: Double,
-        val mathTps: Double,
-        val sortTps: Double,
+        /** Matrix multiplication throughput in GOPS, or GFLOPS for floating point elements. */
+        val mathGops: Double,
+        /** Sorted elements per second. */
+        val sortEps: Double,
+        /** Software-only crypto baseline, `0` unless `CpuConfig.cryptoSoftBaseline` is set. */
+        val cryptoSoftTps: Double,
+        val cryptoAccelerated: Boolean,
+        /** Detected CPU features, one bit per `FEATURE_*` flag. */
+        val features: Long,
+        /** NTT field operations per second, `0` unless `CpuConfig.zk` is set. */
+        val zkNttTps: Double,
+        /** MSM field operations per second, `0` unless `CpuConfig.zk` is set. */
+        val zkMsmTps: Double,
+        /** 2048-bit multiplications per second, `0` unless `CpuConfig.bigint` is set. */
+        val bigintMulTps: Double,
+        /** 2048-bit modular exponentiations per second, `0` unless `CpuConfig.bigint` is set. */
+        val bigintModexpTps: Double,
+        /** Element type of the matrix multiplication, one of the `CpuConfig.MATH_ELEMENT_TYPE_*` values. */
+        val mathElementType: Int,
+        /** Quantized inferences per second, `0` unless `CpuConfig.inference` is set. */
+        val inferenceI8Tps: Double,
+        /** f32 inferences per second, `0` unless `CpuConfig.inference` is set. */
+        val inferenceF32Tps: Double,
     ) {
-        public companion object
+        public fun hasFeature(feature: Long): Boolean = (features and feature) != 0L
+
+        public companion object {
+            public const val FEATURE_NEON: Long = 1L shl 0
+            public const val FEATURE_DOTPROD: Long = 1L shl 1
+            public const val FEATURE_SVE: Long = 1L shl 2
+            public const val FEATURE_I8MM: Long = 1L shl 3
+            public const val FEATURE_SSE41: Long = 1L shl 4
+            public const val FEATURE_AVX2: Long = 1L shl 5
+            public const val FEATURE_AES: Long = 1L shl 6
+            public const val FEATURE_PMULL: Long = 1L shl 7
+            public const val FEATURE_SHA1: Long = 1L shl 8
+            public const val FEATURE_SHA2: Long = 1L shl 9
+            public const val FEATURE_SHA3: Long = 1L shl 10
+            public const val FEATURE_SHA512: Long = 1L shl 11
+        }
     }
 
     public data class RamConfig(
         val allocDataSize: Long = ALLOC_DATA_SIZE_DEFAULT,
         val accessDataSize: Long = ACCESS_DATA_SIZE_DEFAULT,
         val iters: Long = ITERS_DEFAULT,
+        /** Also measures the load-to-use latency. */
+        val latency: Boolean = false,
+        /** Also sweeps the working set through the cache hierarchy. */
+        val sweep: Boolean = false,
+        /** Also runs the STREAM kernels. */
+        val stream: Boolean = false,
+        /** Repeats the concurrent access with 1, 2, 4, ... threads. */
+        val accessScaling: Boolean = false,
+        /** Also runs the allocator churn. */
+        val malloc: Boolean = false,
+        /** Also measures the page fault cost of anonymous mappings. */
+        val mmap: Boolean = false,
+        /** Also probes how much memory can be allocated, within the available memory. */
+        val probe: Boolean = false,
+        /** Also verifies the memory with the test patterns, a failure is thrown as an exception. */
+        val integrity: Boolean = false,
+        /** Also measures the cache coherency traffic between the cores. */
+        val coherency: Boolean = false,
     ) {
         public companion object {
             private const val ALLOC_DATA_SIZE_DEFAULT = 64 * MB
@@ -97,8 +165,55 @@ public class Acubench(context: Context) {
         val accessSequentialAvgTime: Double,
         val accessRandomAvgTime: Double,
         val accessConcurrentAvgTime: Double,
+        /** Average load-to-use latency in nanoseconds, `0` unless `RamConfig.latency` is set. */
+        val latencyAvgNs: Double,
+        /** Largest working set that fits into the last level cache, `0` unless `RamConfig.sweep` is set. */
+        val sweepLlcSize: Long,
+        /** DRAM latency in nanoseconds, `0` unless `RamConfig.sweep` is set. */
+        val sweepDramLatencyNs: Double,
+        /** Peak read bandwidth in GB/s, `0` unless `RamConfig.sweep` is set. */
+        val sweepPeakGbs: Double,
+        /** Single-threaded STREAM triad bandwidth in GB/s, `0` unless `RamConfig.stream` is set. */
+        val streamTriadGbs: Double,
+        /** Multithreaded STREAM triad bandwidth in GB/s, `0` unless `RamConfig.stream` is set. */
+        val streamTriadMultiGbs: Double,
+        /** Single-threaded concurrent access bandwidth in GB/s, `0` unless `RamConfig.accessScaling` is set. */
+        val accessScalingSingleGbs: Double,
+        /** Concurrent access bandwidth with all cores in GB/s, `0` unless `RamConfig.accessScaling` is set. */
+        val accessScalingMultiGbs: Double,
+        /** Single-threaded allocations per second, `0` unless `RamConfig.malloc` is set. */
+        val mallocAps: Double,
+        /** Multithreaded allocations per second, `0` unless `RamConfig.malloc` is set. */
+        val mallocMultiAps: Double,
+        /** Peak growth of the resident set size in bytes, `0` unless `RamConfig.malloc` is set. */
+        val mallocPeakRssGrowth: Long,
+        /** First touch page faults per second, `0` unless `RamConfig.mmap` is set. */
+        val mmapFirstTouchFps: Double,
+        /** First touch time per GiB in seconds, `0` unless `RamConfig.mmap` is set. */
+        val mmapFirstTouchGibTime: Double,
+        /** Largest size allocated by the probe in bytes, `0` unless `RamConfig.probe` is set. */
+        val probeMaxLen: Long,
+        /** Probe allocation throughput in GB/s, `0` unless `RamConfig.probe` is set. */
+        val probeGbs: Double,
+        /** How the probe ended, one of the `PROBE_STATUS_*` values. */
+        val probeStatus: Int,
+        /** Mean write and verify bandwidth over the patterns in GB/s, `0` unless `RamConfig.integrity` is set. */
+        val integrityGbs: Double,
+        /** Increments per second on shared cache lines, `0` unless `RamConfig.coherency` is set. */
+        val coherencySharedOps: Double,
+        /** Increments per second on padded cache lines, `0` unless `RamConfig.coherency` is set. */
+        val coherencyPaddedOps: Double,
+        /** Mean core-to-core latency in nanoseconds, `0` unless `RamConfig.coherency` is set. */
+        val coherencyAvgLatencyNs: Double,
     ) {
-        public companion object
+        public companion object {
+            public const val PROBE_STATUS_NOT_RUN: Int = 0
+            public const val PROBE_STATUS_LIMIT: Int = 1
+            public const val PROBE_STATUS_MARGIN: Int = 2
+            public const val PROBE_STATUS_REFUSED: Int = 3
+            public const val PROBE_STATUS_KILLED: Int = 4
+            public const val PROBE_STATUS_FAILED: Int = 5
+        }
     }
 
     public data class StorageConfig(
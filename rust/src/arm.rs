@@ -5,11 +5,23 @@ pub struct Auxval {
     pub(crate) hwcap: u64,
     pub(crate) hwcap2: u64,
 
+    pub(crate) dotprod_mask: AuxvalMask,
     pub(crate) sve_mask: AuxvalMask,
     pub(crate) i8mm_mask: AuxvalMask,
+
+    pub(crate) aes_mask: AuxvalMask,
+    pub(crate) pmull_mask: AuxvalMask,
+    pub(crate) sha1_mask: AuxvalMask,
+    pub(crate) sha2_mask: AuxvalMask,
+    pub(crate) sha3_mask: AuxvalMask,
+    pub(crate) sha512_mask: AuxvalMask,
 }
 
 impl Auxval {
+    pub(crate) fn dotprod(&self) -> bool {
+        self.is_supported(&self.dotprod_mask)
+    }
+
     pub(crate) fn sve(&self) -> bool {
         self.is_supported(&self.sve_mask)
     }
@@ -18,6 +30,30 @@ impl Auxval {
         self.is_supported(&self.i8mm_mask)
     }
 
+    pub(crate) fn aes(&self) -> bool {
+        self.is_supported(&self.aes_mask)
+    }
+
+    pub(crate) fn pmull(&self) -> bool {
+        self.is_supported(&self.pmull_mask)
+    }
+
+    pub(crate) fn sha1(&self) -> bool {
+        self.is_supported(&self.sha1_mask)
+    }
+
+    pub(crate) fn sha2(&self) -> bool {
+        self.is_supported(&self.sha2_mask)
+    }
+
+    pub(crate) fn sha3(&self) -> bool {
+        self.is_supported(&self.sha3_mask)
+    }
+
+    pub(crate) fn sha512(&self) -> bool {
+        self.is_supported(&self.sha512_mask)
+    }
+
     fn is_supported(&self, mask: &AuxvalMask) -> bool {
         let (vector, mask) = match mask {
             AuxvalMask::HWCAP(mask) => (self.hwcap, mask),
@@ -41,8 +77,18 @@ impl Bench {
             avail_storage,
             CpuFeatures {
                 num_cores: num_cpus::get(),
+                // Advanced SIMD is mandatory on AArch64
+                neon: cfg!(target_arch = "aarch64"),
+                dotprod: auxval.dotprod(),
                 sve: auxval.sve(),
                 i8mm: auxval.i8mm(),
+                aes: auxval.aes(),
+                pmull: auxval.pmull(),
+                sha1: auxval.sha1(),
+                sha2: auxval.sha2(),
+                sha3: auxval.sha3(),
+                sha512: auxval.sha512(),
+                ..Default::default()
             },
         )
     }
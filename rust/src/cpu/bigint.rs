@@ -0,0 +1,819 @@
+use std::{
+    fmt,
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use rand::Rng;
+
+use crate::{
+    utils::{adc, mac, sbb, AddValue, Expirable, GetValue, Timeout},
+    CpuFeatures,
+};
+
+/// Number of operand pairs multiplied in a single multiplication test.
+const MUL_BATCH: usize = 64;
+
+/// Mersenne prime 2^61 - 1, used to cross-check products by their residues.
+const CHECK_PRIME: u64 = (1 << 61) - 1;
+
+pub(crate) fn bench(_features: &CpuFeatures, config: Config) -> Result<Report, Error> {
+    let mut context = Context::new(config, 1);
+    let mut report_builder = ReportBuilder::new(context.bits);
+
+    let mut start: Instant;
+    while !context.timeout.reached() {
+        context.reset_operands();
+
+        start = Instant::now();
+        let ops = black_box(mul::run_test(
+            &context.lhs,
+            &context.rhs,
+            &mut context.products,
+            Some(&context.timeout),
+        ));
+
+        report_builder.add_mul(start.elapsed(), ops);
+
+        if ops.is_ok() {
+            context.verify_products()?;
+        }
+
+        if context.timeout.reached() {
+            break;
+        }
+
+        context.reset_modulus();
+
+        start = Instant::now();
+        let ops = black_box(modexp::run_test(
+            &context.montgomery,
+            &context.bases,
+            &context.exponents,
+            &mut context.powers,
+            Some(&context.timeout),
+        ));
+
+        report_builder.add_modexp(start.elapsed(), ops);
+
+        if ops.is_ok() {
+            context.verify_powers()?;
+        }
+    }
+
+    Ok(report_builder.build())
+}
+
+pub(crate) fn bench_multithread(features: &CpuFeatures, config: Config) -> Result<Report, Error> {
+    let mut context = Context::new(config, features.num_cores);
+    let threadpool = rayon::ThreadPoolBuilder::new()
+        .num_threads(features.num_cores)
+        .build()
+        .unwrap();
+    let mut report_builder = ReportBuilder::new(context.bits);
+
+    let mut start: Instant;
+    while !context.timeout.reached() {
+        context.reset_operands();
+
+        start = Instant::now();
+        let ops = black_box(mul::run_test_multithread(
+            &threadpool,
+            &context.lhs,
+            &context.rhs,
+            &mut context.products,
+            Some(&context.timeout),
+        ));
+
+        report_builder.add_mul(start.elapsed(), ops);
+
+        if ops.is_ok() {
+            context.verify_products()?;
+        }
+
+        if context.timeout.reached() {
+            break;
+        }
+
+        context.reset_modulus();
+
+        start = Instant::now();
+        let ops = black_box(modexp::run_test_multithread(
+            &threadpool,
+            &context.montgomery,
+            &context.bases,
+            &context.exponents,
+            &mut context.powers,
+            Some(&context.timeout),
+        ));
+
+        report_builder.add_modexp(start.elapsed(), ops);
+
+        if ops.is_ok() {
+            context.verify_powers()?;
+        }
+    }
+
+    Ok(report_builder.build())
+}
+
+fn merge_results(acc: Result<u64, u64>, next: Result<u64, u64>) -> Result<u64, u64> {
+    match (acc, next) {
+        (Ok(acc), Ok(next)) => Ok(acc + next),
+        _ => Err(acc.value() + next.value()),
+    }
+}
+
+/// Schoolbook multiplication of `n`-limb integers into `2n`-limb products.
+/// Counts 64-bit limb multiply-accumulates as ops.
+mod mul {
+    use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
+
+    use super::*;
+
+    pub(super) fn run_test(
+        lhs: &[Vec<u64>],
+        rhs: &[Vec<u64>],
+        products: &mut [Vec<u64>],
+        timeout: Option<&Timeout>,
+    ) -> Result<u64, u64> {
+        let mut ops = 0;
+        for ((a, b), product) in lhs.iter().zip(rhs).zip(products.iter_mut()) {
+            timeout.reached_with_err(ops)?;
+
+            ops += mul(a, b, product);
+        }
+
+        Ok(ops)
+    }
+
+    pub(super) fn run_test_multithread(
+        threadpool: &rayon::ThreadPool,
+        lhs: &[Vec<u64>],
+        rhs: &[Vec<u64>],
+        products: &mut [Vec<u64>],
+        timeout: Option<&Timeout>,
+    ) -> Result<u64, u64> {
+        threadpool.install(|| {
+            lhs.into_par_iter()
+                .zip(rhs)
+                .zip(products)
+                .map(|((a, b), product)| {
+                    if timeout.reached() {
+                        return Err(0);
+                    }
+
+                    Ok(mul(a, b, product))
+                })
+                .reduce(|| Ok(0), merge_results)
+        })
+    }
+
+    pub(super) fn mul(a: &[u64], b: &[u64], product: &mut [u64]) -> u64 {
+        let n = a.len();
+        product.fill(0);
+
+        for (i, &a_i) in a.iter().enumerate() {
+            let mut carry = 0;
+            for (j, &b_j) in b.iter().enumerate() {
+                (product[i + j], carry) = mac(product[i + j], a_i, b_j, carry);
+            }
+            product[i + n] = carry;
+        }
+
+        (n * n) as u64
+    }
+}
+
+/// Left-to-right square-and-multiply modular exponentiation in the Montgomery domain.
+/// Counts 64-bit limb multiply-accumulates as ops.
+mod modexp {
+    use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
+
+    use super::*;
+
+    pub(super) fn run_test(
+        montgomery: &Montgomery,
+        bases: &[Vec<u64>],
+        exponents: &[Vec<u64>],
+        powers: &mut [Vec<u64>],
+        timeout: Option<&Timeout>,
+    ) -> Result<u64, u64> {
+        let mut ops = 0;
+        for ((base, exponent), power) in bases.iter().zip(exponents).zip(powers.iter_mut()) {
+            ops = pow(montgomery, base, exponent, power, timeout).add(ops)?;
+        }
+
+        Ok(ops)
+    }
+
+    pub(super) fn run_test_multithread(
+        threadpool: &rayon::ThreadPool,
+        montgomery: &Montgomery,
+        bases: &[Vec<u64>],
+        exponents: &[Vec<u64>],
+        powers: &mut [Vec<u64>],
+        timeout: Option<&Timeout>,
+    ) -> Result<u64, u64> {
+        threadpool.install(|| {
+            bases
+                .into_par_iter()
+                .zip(exponents)
+                .zip(powers)
+                .map(|((base, exponent), power)| pow(montgomery, base, exponent, power, timeout))
+                .reduce(|| Ok(0), merge_results)
+        })
+    }
+
+    pub(super) fn pow(
+        montgomery: &Montgomery,
+        base: &[u64],
+        exponent: &[u64],
+        power: &mut [u64],
+        timeout: Option<&Timeout>,
+    ) -> Result<u64, u64> {
+        let mut ops = 0;
+
+        let mut scratch = vec![0u64; montgomery.len() + 2];
+        let mut base_m = vec![0u64; montgomery.len()];
+        let mut acc = montgomery.one.clone();
+        let mut tmp = vec![0u64; montgomery.len()];
+
+        ops += montgomery.mul(base, &montgomery.r2, &mut base_m, &mut scratch);
+
+        for bit in (0..exponent.len() * 64).rev() {
+            timeout.reached_with_err(ops)?;
+
+            ops += montgomery.mul(&acc, &acc, &mut tmp, &mut scratch);
+            if (exponent[bit / 64] >> (bit % 64)) & 1 == 1 {
+                ops += montgomery.mul(&tmp, &base_m, &mut acc, &mut scratch);
+            } else {
+                std::mem::swap(&mut acc, &mut tmp);
+            }
+        }
+
+        // convert back from the Montgomery domain
+        tmp.fill(0);
+        tmp[0] = 1;
+        ops += montgomery.mul(&acc, &tmp, power, &mut scratch);
+
+        Ok(ops)
+    }
+}
+
+/// Montgomery arithmetic modulo an odd `n`-limb modulus with `R = 2^(64n)`.
+struct Montgomery {
+    modulus: Vec<u64>,
+    /// -modulus^(-1) mod 2^64
+    inv: u64,
+    /// R mod modulus
+    one: Vec<u64>,
+    /// R^2 mod modulus
+    r2: Vec<u64>,
+}
+
+impl Montgomery {
+    fn new(modulus: Vec<u64>) -> Self {
+        let n = modulus.len();
+
+        // Newton iteration, each step doubles the number of correct low bits
+        let mut inv = 1u64;
+        for _ in 0..6 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(modulus[0].wrapping_mul(inv)));
+        }
+        let inv = inv.wrapping_neg();
+
+        let mut one = vec![0u64; n];
+        one[0] = 1;
+        for _ in 0..n * 64 {
+            double_mod(&mut one, &modulus);
+        }
+
+        let mut r2 = one.clone();
+        for _ in 0..n * 64 {
+            double_mod(&mut r2, &modulus);
+        }
+
+        Self {
+            modulus,
+            inv,
+            one,
+            r2,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.modulus.len()
+    }
+
+    /// `output = a * b / R mod modulus` (CIOS), `scratch` must hold `n + 2` limbs.
+    fn mul(&self, a: &[u64], b: &[u64], output: &mut [u64], scratch: &mut [u64]) -> u64 {
+        let n = self.len();
+        let t = scratch;
+        t.fill(0);
+
+        for &b_i in b {
+            let mut carry = 0;
+            for (t_j, &a_j) in t.iter_mut().zip(a) {
+                (*t_j, carry) = mac(*t_j, a_j, b_i, carry);
+            }
+            (t[n], t[n + 1]) = adc(t[n], carry, 0);
+
+            let m = t[0].wrapping_mul(self.inv);
+            let (_, mut carry) = mac(t[0], m, self.modulus[0], 0);
+            for j in 1..n {
+                (t[j - 1], carry) = mac(t[j], m, self.modulus[j], carry);
+            }
+            (t[n - 1], carry) = adc(t[n], carry, 0);
+            t[n] = t[n + 1] + carry;
+        }
+
+        output.copy_from_slice(&t[..n]);
+        if t[n] != 0 || !less_than(output, &self.modulus) {
+            sub_assign(output, &self.modulus);
+        }
+
+        (2 * n * n + n) as u64
+    }
+}
+
+/// `value = 2 * value mod modulus`, for `value < modulus`.
+fn double_mod(value: &mut [u64], modulus: &[u64]) {
+    let mut carry = 0;
+    for limb in value.iter_mut() {
+        let next = *limb >> 63;
+        *limb = (*limb << 1) | carry;
+        carry = next;
+    }
+
+    if carry != 0 || !less_than(value, modulus) {
+        sub_assign(value, modulus);
+    }
+}
+
+fn less_than(a: &[u64], b: &[u64]) -> bool {
+    for (a, b) in a.iter().rev().zip(b.iter().rev()) {
+        if a != b {
+            return a < b;
+        }
+    }
+
+    false
+}
+
+fn sub_assign(a: &mut [u64], b: &[u64]) {
+    let mut borrow = 0;
+    for (a, &b) in a.iter_mut().zip(b) {
+        (*a, borrow) = sbb(*a, b, borrow);
+    }
+}
+
+/// Reference exponentiation for verifying sampled powers, independent of [Montgomery]:
+/// square-and-multiply with schoolbook products reduced by long division.
+fn reference_pow(base: &[u64], exponent: &[u64], modulus: &[u64]) -> Vec<u64> {
+    let n = modulus.len();
+    let mut acc = vec![0u64; n];
+    acc[0] = 1;
+    let mut product = vec![0u64; 2 * n + 1];
+
+    let mut mul_mod = |acc: &mut [u64], b: &[u64]| {
+        mul::mul(acc, b, &mut product[..2 * n]);
+        product[2 * n] = 0;
+        rem_assign(&mut product, modulus);
+        acc.copy_from_slice(&product[..n]);
+    };
+
+    for bit in (0..exponent.len() * 64).rev() {
+        let square = acc.clone();
+        mul_mod(&mut acc, &square);
+        if (exponent[bit / 64] >> (bit % 64)) & 1 == 1 {
+            mul_mod(&mut acc, base);
+        }
+    }
+
+    acc
+}
+
+/// Knuth's algorithm D, leaves `value mod modulus` in the low limbs of `value`.
+/// `modulus` must have its top bit set, `value` needs a zero top limb.
+fn rem_assign(value: &mut [u64], modulus: &[u64]) {
+    let n = modulus.len();
+    let (v_1, v_2) = (
+        modulus[n - 1] as u128,
+        *modulus.get(n.wrapping_sub(2)).unwrap_or(&0) as u128,
+    );
+
+    for j in (0..value.len() - n).rev() {
+        let u = &mut value[j..=j + n];
+
+        // estimate the quotient limb from the top limbs, at most one too large after the correction
+        let top = ((u[n] as u128) << 64) | u[n - 1] as u128;
+        let mut q = top / v_1;
+        let mut r = top % v_1;
+        let u_2 = if n >= 2 { u[n - 2] as u128 } else { 0 };
+        while q > u64::MAX as u128 || (r <= u64::MAX as u128 && q * v_2 > (r << 64) | u_2) {
+            q -= 1;
+            r += v_1;
+        }
+
+        let mut carry = 0;
+        let mut borrow = 0;
+        for (u_i, &v_i) in u.iter_mut().zip(modulus) {
+            let p;
+            (p, carry) = mac(0, q as u64, v_i, carry);
+            (*u_i, borrow) = sbb(*u_i, p, borrow);
+        }
+        (u[n], borrow) = sbb(u[n], carry, borrow);
+
+        if borrow != 0 {
+            let mut carry = 0;
+            for (u_i, &v_i) in u.iter_mut().zip(modulus) {
+                (*u_i, carry) = adc(*u_i, v_i, carry);
+            }
+            u[n] = u[n].wrapping_add(carry);
+        }
+    }
+}
+
+/// Random odd `n`-limb modulus with the top bit set.
+fn random_modulus<R: Rng + ?Sized>(rng: &mut R, n: usize) -> Vec<u64> {
+    let mut modulus = vec![0u64; n];
+    rng.fill(&mut modulus[..]);
+    modulus[0] |= 1;
+    modulus[n - 1] |= 1 << 63;
+
+    modulus
+}
+
+/// Residue of a little-endian integer modulo [CHECK_PRIME].
+fn residue(value: &[u64]) -> u64 {
+    value.iter().rev().fold(0, |acc, &limb| {
+        ((((acc as u128) << 64) + limb as u128) % CHECK_PRIME as u128) as u64
+    })
+}
+
+pub struct Config {
+    pub rng: Box<dyn rand::RngCore>,
+
+    pub duration: Duration,
+
+    /// Operand size, rounded up to a multiple of 64 bits, e.g. 2048 or 4096.
+    pub bits: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            rng: Box::new(rand::thread_rng()),
+            duration: Duration::from_secs(10),
+            bits: 2048,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    ProductMismatch(usize),
+    PowerOutOfRange(usize),
+    PowerMismatch(usize),
+}
+
+pub struct Report {
+    pub bits: usize,
+
+    pub mul_duration: Duration,
+    pub mul_ops: u64,
+    pub mul_tps: f64,
+
+    pub modexp_duration: Duration,
+    pub modexp_ops: u64,
+    pub modexp_tps: f64,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "bigint ({} bit) ... mul {} ops/s, modexp {} ops/s",
+            self.bits,
+            self.mul_tps.floor(),
+            self.modexp_tps.floor()
+        )
+    }
+}
+
+struct ReportBuilder {
+    bits: usize,
+
+    mul_duration: Duration,
+    mul_ops: u64,
+
+    modexp_duration: Duration,
+    modexp_ops: u64,
+}
+
+impl ReportBuilder {
+    fn new(bits: usize) -> Self {
+        Self {
+            bits,
+            mul_duration: Duration::ZERO,
+            mul_ops: 0,
+            modexp_duration: Duration::ZERO,
+            modexp_ops: 0,
+        }
+    }
+
+    fn add_mul(&mut self, duration: Duration, result: Result<u64, u64>) {
+        self.mul_duration += duration;
+        self.mul_ops += result.value();
+    }
+
+    fn add_modexp(&mut self, duration: Duration, result: Result<u64, u64>) {
+        self.modexp_duration += duration;
+        self.modexp_ops += result.value();
+    }
+
+    fn build(self) -> Report {
+        Report {
+            bits: self.bits,
+            mul_duration: self.mul_duration,
+            mul_ops: self.mul_ops,
+            mul_tps: tps(self.mul_duration, self.mul_ops),
+            modexp_duration: self.modexp_duration,
+            modexp_ops: self.modexp_ops,
+            modexp_tps: tps(self.modexp_duration, self.modexp_ops),
+        }
+    }
+}
+
+fn tps(duration: Duration, ops: u64) -> f64 {
+    match duration {
+        Duration::ZERO => 0.,
+        _ => ops as f64 / duration.as_secs_f64(),
+    }
+}
+
+struct Context {
+    rng: Box<dyn rand::RngCore>,
+
+    bits: usize,
+
+    lhs: Vec<Vec<u64>>,
+    rhs: Vec<Vec<u64>>,
+    products: Vec<Vec<u64>>,
+
+    montgomery: Montgomery,
+    bases: Vec<Vec<u64>>,
+    exponents: Vec<Vec<u64>>,
+    powers: Vec<Vec<u64>>,
+
+    timeout: Timeout,
+}
+
+impl Context {
+    fn new(mut config: Config, batch: usize) -> Self {
+        let n = config.bits.div_ceil(64).max(1);
+        let operands = |count: usize, len: usize| vec![vec![0u64; len]; count];
+
+        let montgomery = Montgomery::new(random_modulus(&mut config.rng, n));
+
+        let timeout = Timeout::new(config.duration);
+
+        Self {
+            rng: config.rng,
+            bits: n * 64,
+            lhs: operands(MUL_BATCH, n),
+            rhs: operands(MUL_BATCH, n),
+            products: operands(MUL_BATCH, 2 * n),
+            montgomery,
+            bases: operands(batch, n),
+            exponents: operands(batch, n),
+            powers: operands(batch, n),
+            timeout,
+        }
+    }
+
+    fn reset_operands(&mut self) {
+        for operand in self.lhs.iter_mut().chain(self.rhs.iter_mut()) {
+            self.rng.fill(&mut operand[..]);
+        }
+    }
+
+    /// Picks a new odd modulus with the top bit set, and random bases below it.
+    fn reset_modulus(&mut self) {
+        self.montgomery = Montgomery::new(random_modulus(&mut self.rng, self.montgomery.len()));
+
+        for base in self.bases.iter_mut() {
+            self.rng.fill(&mut base[..]);
+            *base.last_mut().unwrap() &= !(1 << 63);
+        }
+        for exponent in self.exponents.iter_mut() {
+            self.rng.fill(&mut exponent[..]);
+        }
+    }
+
+    fn verify_products(&self) -> Result<(), Error> {
+        for (i, ((a, b), product)) in self
+            .lhs
+            .iter()
+            .zip(&self.rhs)
+            .zip(&self.products)
+            .enumerate()
+        {
+            let expected = (residue(a) as u128 * residue(b) as u128 % CHECK_PRIME as u128) as u64;
+            if residue(product) != expected {
+                return Err(Error::ProductMismatch(i));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that all powers are reduced, and one randomly chosen power against [reference_pow].
+    fn verify_powers(&mut self) -> Result<(), Error> {
+        for (i, power) in self.powers.iter().enumerate() {
+            if !less_than(power, &self.montgomery.modulus) {
+                return Err(Error::PowerOutOfRange(i));
+            }
+        }
+
+        let i = self.rng.gen_range(0..self.powers.len());
+        let expected = reference_pow(&self.bases[i], &self.exponents[i], &self.montgomery.modulus);
+        if self.powers[i] != expected {
+            return Err(Error::PowerMismatch(i));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use num_bigint::BigUint;
+
+    use super::*;
+
+    fn to_biguint(value: &[u64]) -> BigUint {
+        BigUint::from_bytes_le(
+            &value
+                .iter()
+                .flat_map(|limb| limb.to_le_bytes())
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    fn random(rng: &mut impl Rng, n: usize) -> Vec<u64> {
+        (0..n).map(|_| rng.gen()).collect()
+    }
+
+    #[test]
+    fn test_bench() {
+        let duration = Duration::from_millis(1000);
+        let start = Instant::now();
+        let result = bench(
+            &CpuFeatures {
+                num_cores: 1,
+                ..Default::default()
+            },
+            Config {
+                duration,
+                bits: 512,
+                ..Default::default()
+            },
+        );
+        let elapsed = start.elapsed();
+
+        assert!(result.is_ok(), "expected success");
+        let result = result.unwrap();
+        assert!(result.mul_ops > 0);
+        assert!(result.mul_tps > 0.);
+        assert!(elapsed >= duration && elapsed <= duration + Duration::from_millis(200));
+
+        println!("{result}");
+    }
+
+    #[test]
+    fn test_bench_multithread() {
+        let duration = Duration::from_millis(1000);
+        let start = Instant::now();
+        let result = bench_multithread(
+            &CpuFeatures {
+                num_cores: 8,
+                ..Default::default()
+            },
+            Config {
+                duration,
+                bits: 512,
+                ..Default::default()
+            },
+        );
+        let elapsed = start.elapsed();
+
+        assert!(result.is_ok(), "expected success");
+        let result = result.unwrap();
+        assert!(result.mul_ops > 0);
+        assert!(result.mul_tps > 0.);
+        assert!(elapsed >= duration && elapsed <= duration + Duration::from_millis(200));
+
+        println!("{result}");
+    }
+
+    #[test]
+    fn test_mul() {
+        let mut rng = rand::thread_rng();
+        let lhs = (0..4).map(|_| random(&mut rng, 32)).collect::<Vec<_>>();
+        let rhs = (0..4).map(|_| random(&mut rng, 32)).collect::<Vec<_>>();
+        let mut products = vec![vec![0u64; 64]; 4];
+
+        let result = mul::run_test(&lhs, &rhs, &mut products, None);
+
+        assert!(result.is_ok(), "expected success");
+        assert_eq!(4 * 32 * 32, result.unwrap());
+        for i in 0..4 {
+            assert_eq!(
+                to_biguint(&lhs[i]) * to_biguint(&rhs[i]),
+                to_biguint(&products[i])
+            );
+        }
+    }
+
+    #[test]
+    fn test_reference_pow() {
+        let mut rng = rand::thread_rng();
+
+        for n in [1, 2, 8] {
+            let modulus = random_modulus(&mut rng, n);
+            let base = random(&mut rng, n);
+            let exponent = random(&mut rng, n);
+
+            let expected = to_biguint(&base).modpow(&to_biguint(&exponent), &to_biguint(&modulus));
+            assert_eq!(
+                expected,
+                to_biguint(&reference_pow(&base, &exponent, &modulus))
+            );
+        }
+    }
+
+    #[test]
+    fn test_verify_powers() {
+        let mut context = Context::new(
+            Config {
+                bits: 256,
+                ..Default::default()
+            },
+            1,
+        );
+        context.reset_modulus();
+
+        let result = modexp::run_test(
+            &context.montgomery,
+            &context.bases,
+            &context.exponents,
+            &mut context.powers,
+            None,
+        );
+
+        assert!(result.is_ok(), "expected success");
+        assert!(context.verify_powers().is_ok());
+
+        // still in range, but wrong
+        context.powers[0][0] ^= 1;
+        assert!(matches!(
+            context.verify_powers(),
+            Err(Error::PowerMismatch(0))
+        ));
+    }
+
+    #[test]
+    fn test_modexp() {
+        let mut rng = rand::thread_rng();
+        let modulus = random_modulus(&mut rng, 32);
+        let montgomery = Montgomery::new(modulus.clone());
+
+        let bases = vec![random(&mut rng, 32), vec![0u64; 32]];
+        let exponents = vec![random(&mut rng, 32), random(&mut rng, 32)];
+        let mut powers = vec![vec![0u64; 32]; 2];
+
+        let threadpool = rayon::ThreadPoolBuilder::new()
+            .num_threads(2)
+            .build()
+            .unwrap();
+        let result = modexp::run_test_multithread(
+            &threadpool,
+            &montgomery,
+            &bases,
+            &exponents,
+            &mut powers,
+            None,
+        );
+
+        assert!(result.is_ok(), "expected success");
+        for i in 0..2 {
+            let expected =
+                to_biguint(&bases[i]).modpow(&to_biguint(&exponents[i]), &to_biguint(&modulus));
+            assert_eq!(expected, to_biguint(&powers[i]));
+        }
+    }
+}
@@ -21,17 +21,42 @@ const ENC_BLOCK_SIZE: usize = 16;
 
 const HASH_SIZE: usize = 32;
 
-pub(crate) fn bench(_features: &CpuFeatures, config: Config) -> Result<Report, Error> {
+pub(crate) fn bench(features: &CpuFeatures, config: Config) -> Result<Report, Error> {
+    let soft_baseline = config.soft_baseline;
     let mut context = Context::new(config);
-    let mut report_builder = ReportBuilder::new();
+    let mut report_builder = ReportBuilder::new(features);
 
+    let cipher = Cipher::with_key(&context.key);
+    let mut hasher = Hasher::init();
+    run(&mut context, &mut report_builder, &cipher, &mut hasher)?;
+
+    if soft_baseline {
+        context.reset_timeout();
+        let mut soft_report_builder = ReportBuilder::new(features);
+
+        let cipher = soft::Cipher::with_key(&context.key);
+        let mut hasher = soft::Hasher::init();
+        run(&mut context, &mut soft_report_builder, &cipher, &mut hasher)?;
+
+        report_builder.set_soft(soft_report_builder);
+    }
+
+    Ok(report_builder.build())
+}
+
+fn run<C: BlockCipher, H: Hash>(
+    context: &mut Context,
+    report_builder: &mut ReportBuilder,
+    cipher: &C,
+    hasher: &mut H,
+) -> Result<(), Error> {
     let mut start: Instant;
     'main: while !context.timeout.reached() {
         context.reset_data();
 
         start = Instant::now();
         let bytes = black_box(encryption::run_test(
-            &context.cipher,
+            cipher,
             &context.data[..],
             &mut context.encrypted[..],
             &mut context.decrypted[..],
@@ -47,7 +72,10 @@ pub(crate) fn bench(_features: &CpuFeatures, config: Config) -> Result<Report, E
                 }
 
                 if context.data[i] != context.decrypted[i] {
-                    return Err(Error::EncryptionMismatch(context.data, context.decrypted));
+                    return Err(Error::EncryptionMismatch(
+                        context.data.clone(),
+                        context.decrypted.clone(),
+                    ));
                 }
             }
         }
@@ -58,7 +86,7 @@ pub(crate) fn bench(_features: &CpuFeatures, config: Config) -> Result<Report, E
 
         start = Instant::now();
         let bytes = black_box(hash::run_test(
-            &mut context.hasher,
+            hasher,
             &context.data[..],
             &mut context.hash[..],
             Some(&context.timeout),
@@ -85,25 +113,48 @@ pub(crate) fn bench(_features: &CpuFeatures, config: Config) -> Result<Report, E
         }
     }
 
-    Ok(report_builder.build())
+    Ok(())
 }
 
 pub(crate) fn bench_multithread(features: &CpuFeatures, config: Config) -> Result<Report, Error> {
+    let soft_baseline = config.soft_baseline;
     let mut context = Context::new(config);
     let threadpool = rayon::ThreadPoolBuilder::new()
         .num_threads(features.num_cores)
         .build()
         .unwrap();
-    let mut report_builder = ReportBuilder::new();
+    let mut report_builder = ReportBuilder::new(features);
+
+    let cipher = Cipher::with_key(&context.key);
+    run_multithread(&threadpool, &mut context, &mut report_builder, &cipher)?;
+
+    if soft_baseline {
+        context.reset_timeout();
+        let mut soft_report_builder = ReportBuilder::new(features);
+
+        let cipher = soft::Cipher::with_key(&context.key);
+        run_multithread(&threadpool, &mut context, &mut soft_report_builder, &cipher)?;
 
+        report_builder.set_soft(soft_report_builder);
+    }
+
+    Ok(report_builder.build())
+}
+
+fn run_multithread<C: BlockCipher>(
+    threadpool: &rayon::ThreadPool,
+    context: &mut Context,
+    report_builder: &mut ReportBuilder,
+    cipher: &C,
+) -> Result<(), Error> {
     let mut start: Instant;
     'main: while !context.timeout.reached() {
-        context.rng.fill_bytes(&mut context.data);
+        context.reset_data();
 
         start = Instant::now();
         let bytes = black_box(encryption::run_test_multithread(
-            &threadpool,
-            &context.cipher,
+            threadpool,
+            cipher,
             &context.data[..],
             &mut context.encrypted[..],
             &mut context.decrypted[..],
@@ -119,13 +170,102 @@ pub(crate) fn bench_multithread(features: &CpuFeatures, config: Config) -> Resul
                 }
 
                 if context.data[i] != context.decrypted[i] {
-                    return Err(Error::EncryptionMismatch(context.data, context.decrypted));
+                    return Err(Error::EncryptionMismatch(
+                        context.data.clone(),
+                        context.decrypted.clone(),
+                    ));
                 }
             }
         }
     }
 
-    Ok(report_builder.build())
+    Ok(())
+}
+
+trait BlockCipher: Sync {
+    fn with_key(key: &[u8; ENC_KEY_SIZE]) -> Self;
+
+    fn encrypt(&self, block: &[u8], output: &mut [u8]);
+    fn decrypt(&self, block: &[u8], output: &mut [u8]);
+}
+
+trait Hash {
+    fn init() -> Self;
+
+    fn hash(&mut self, data: &[u8], output: &mut [u8]);
+}
+
+impl BlockCipher for Cipher {
+    fn with_key(key: &[u8; ENC_KEY_SIZE]) -> Self {
+        Cipher::new(GenericArray::from_slice(key))
+    }
+
+    fn encrypt(&self, block: &[u8], output: &mut [u8]) {
+        self.encrypt_block_b2b(
+            GenericArray::from_slice(block),
+            GenericArray::from_mut_slice(output),
+        );
+    }
+
+    fn decrypt(&self, block: &[u8], output: &mut [u8]) {
+        self.decrypt_block_b2b(
+            GenericArray::from_slice(block),
+            GenericArray::from_mut_slice(output),
+        );
+    }
+}
+
+impl Hash for Hasher {
+    fn init() -> Self {
+        Hasher::new()
+    }
+
+    fn hash(&mut self, data: &[u8], output: &mut [u8]) {
+        self.update(data);
+        self.finalize_into_reset(GenericArray::from_mut_slice(output));
+    }
+}
+
+/// Portable implementations of the same algorithms, compiled without hardware acceleration.
+/// Used as a baseline to show the speedup gained from the AES and SHA CPU extensions.
+mod soft {
+    use aes_soft::cipher::{
+        generic_array::GenericArray, BlockDecrypt, BlockEncrypt, NewBlockCipher,
+    };
+    use sha2_soft::Digest;
+
+    use super::ENC_KEY_SIZE;
+
+    pub(super) struct Cipher(aes_soft::Aes256);
+
+    pub(super) struct Hasher(sha2_soft::Sha256);
+
+    impl super::BlockCipher for Cipher {
+        fn with_key(key: &[u8; ENC_KEY_SIZE]) -> Self {
+            Self(aes_soft::Aes256::new(GenericArray::from_slice(key)))
+        }
+
+        fn encrypt(&self, block: &[u8], output: &mut [u8]) {
+            output.copy_from_slice(block);
+            self.0.encrypt_block(GenericArray::from_mut_slice(output));
+        }
+
+        fn decrypt(&self, block: &[u8], output: &mut [u8]) {
+            output.copy_from_slice(block);
+            self.0.decrypt_block(GenericArray::from_mut_slice(output));
+        }
+    }
+
+    impl super::Hash for Hasher {
+        fn init() -> Self {
+            Self(sha2_soft::Sha256::new())
+        }
+
+        fn hash(&mut self, data: &[u8], output: &mut [u8]) {
+            self.0.update(data);
+            output.copy_from_slice(&self.0.finalize_reset());
+        }
+    }
 }
 
 mod encryption {
@@ -133,8 +273,8 @@ mod encryption {
 
     use super::*;
 
-    pub(super) fn run_test(
-        cipher: &Cipher,
+    pub(super) fn run_test<C: BlockCipher>(
+        cipher: &C,
         data: &[u8],
         enc_output: &mut [u8],
         dec_output: &mut [u8],
@@ -153,9 +293,9 @@ mod encryption {
         Ok(bytes_count)
     }
 
-    pub(super) fn run_test_multithread(
+    pub(super) fn run_test_multithread<C: BlockCipher>(
         threadpool: &rayon::ThreadPool,
-        cipher: &Cipher,
+        cipher: &C,
         data: &[u8],
         enc_output: &mut [u8],
         dec_output: &mut [u8],
@@ -214,8 +354,8 @@ mod encryption {
         (data, enc_output, dec_output)
     }
 
-    fn encrypt_decrypt_block(
-        cipher: &Cipher,
+    fn encrypt_decrypt_block<C: BlockCipher>(
+        cipher: &C,
         data: &[u8],
         enc_output: &mut [u8],
         dec_output: &mut [u8],
@@ -223,17 +363,13 @@ mod encryption {
     ) -> Result<u64, u64> {
         let mut bytes_count = 0u64;
 
-        let data_block = GenericArray::from_slice(data);
-        let enc_block = GenericArray::from_mut_slice(enc_output);
-        let dec_block = GenericArray::from_mut_slice(dec_output);
-
-        cipher.encrypt_block_b2b(data_block, enc_block);
-        bytes_count += enc_block.len() as u64;
+        cipher.encrypt(data, enc_output);
+        bytes_count += enc_output.len() as u64;
 
         timeout.reached_with_err(bytes_count)?;
 
-        cipher.decrypt_block_b2b(enc_block, dec_block);
-        bytes_count += dec_block.len() as u64;
+        cipher.decrypt(enc_output, dec_output);
+        bytes_count += dec_output.len() as u64;
 
         Ok(bytes_count)
     }
@@ -242,16 +378,13 @@ mod encryption {
 mod hash {
     use super::*;
 
-    pub(super) fn run_test(
-        hasher: &mut Hasher,
+    pub(super) fn run_test<H: Hash>(
+        hasher: &mut H,
         data: &[u8],
         output: &mut [u8],
         _timeout: Option<&Timeout>,
     ) -> Result<u64, u64> {
-        let out = GenericArray::from_mut_slice(output);
-
-        hasher.update(&data[..]);
-        hasher.finalize_into_reset(out);
+        hasher.hash(data, output);
 
         Ok(output.len() as u64)
     }
@@ -264,6 +397,9 @@ pub struct Config {
 
     pub enc_key: Option<[u8; ENC_KEY_SIZE]>,
     pub data_len: usize,
+
+    /// Repeat the benchmark for another `duration` with software-only AES and SHA-256.
+    pub soft_baseline: bool,
 }
 
 impl Default for Config {
@@ -273,6 +409,7 @@ impl Default for Config {
             duration: Duration::from_secs(10),
             enc_key: None,
             data_len: 10 * KB,
+            soft_baseline: false,
         }
     }
 }
@@ -288,24 +425,44 @@ pub struct Report {
     pub duration: Duration,
     pub bytes_count: u64,
     pub tps: f64,
+
+    pub accelerated: bool,
+    pub soft_tps: Option<f64>,
 }
 
 impl fmt::Display for Report {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "crypto ... {} bytes/s", self.tps.floor())
+        write!(f, "crypto ... {} bytes/s", self.tps.floor())?;
+        if self.accelerated {
+            write!(f, " (accelerated)")?;
+        }
+        if let Some(soft_tps) = self.soft_tps {
+            write!(f, ", software {} bytes/s", soft_tps.floor())?;
+            if soft_tps > 0. {
+                write!(f, " (x{:.2})", self.tps / soft_tps)?;
+            }
+        }
+
+        Ok(())
     }
 }
 
 struct ReportBuilder {
+    accelerated: bool,
+
     duration: Duration,
     bytes_count: u64,
+
+    soft: Option<Box<ReportBuilder>>,
 }
 
 impl ReportBuilder {
-    fn new() -> Self {
+    fn new(features: &CpuFeatures) -> Self {
         Self {
+            accelerated: features.aes && features.sha2,
             duration: Duration::ZERO,
             bytes_count: 0,
+            soft: None,
         }
     }
 
@@ -314,14 +471,24 @@ impl ReportBuilder {
         self.bytes_count += result.value();
     }
 
+    fn set_soft(&mut self, soft: ReportBuilder) {
+        self.soft = Some(Box::new(soft));
+    }
+
+    fn tps(&self) -> f64 {
+        match self.duration {
+            Duration::ZERO => 0.,
+            _ => self.bytes_count as f64 / self.duration.as_secs_f64(),
+        }
+    }
+
     fn build(self) -> Report {
         Report {
             duration: self.duration,
             bytes_count: self.bytes_count,
-            tps: match self.duration {
-                Duration::ZERO => 0.,
-                _ => self.bytes_count as f64 / self.duration.as_secs_f64(),
-            },
+            tps: self.tps(),
+            accelerated: self.accelerated,
+            soft_tps: self.soft.map(|soft| soft.tps()),
         }
     }
 }
@@ -329,8 +496,7 @@ impl ReportBuilder {
 struct Context {
     rng: Box<dyn rand::RngCore>,
 
-    cipher: Cipher,
-    hasher: Hasher,
+    key: [u8; ENC_KEY_SIZE],
 
     data: Vec<u8>,
     encrypted: Vec<u8>,
@@ -351,10 +517,6 @@ impl Context {
                 key
             }
         };
-        let key = GenericArray::from(key);
-        let cipher = Cipher::new(&key);
-
-        let hasher = Hasher::new();
 
         let data_len = config.data_len - (config.data_len % 16);
         let data = vec_with_len!(data_len);
@@ -367,8 +529,7 @@ impl Context {
 
         Self {
             rng: config.rng,
-            cipher,
-            hasher,
+            key,
             data,
             encrypted,
             decrypted,
@@ -380,6 +541,10 @@ impl Context {
     fn reset_data(&mut self) {
         self.rng.fill_bytes(&mut self.data);
     }
+
+    fn reset_timeout(&mut self) {
+        self.timeout = Timeout::new(self.timeout.duration);
+    }
 }
 
 #[cfg(test)]
@@ -397,8 +562,7 @@ mod tests {
         let result = bench(
             &CpuFeatures {
                 num_cores: 1,
-                sve: false,
-                i8mm: false,
+                ..Default::default()
             },
             Config {
                 duration,
@@ -424,8 +588,7 @@ mod tests {
         let result = bench_multithread(
             &CpuFeatures {
                 num_cores: 8,
-                sve: false,
-                i8mm: false,
+                ..Default::default()
             },
             Config {
                 duration,
@@ -444,6 +607,30 @@ mod tests {
         println!("{result}");
     }
 
+    #[test]
+    fn test_bench_soft_baseline() {
+        let duration = Duration::from_millis(500);
+        let result = bench(
+            &CpuFeatures {
+                num_cores: 1,
+                ..Default::default()
+            },
+            Config {
+                duration,
+                data_len: 64,
+                soft_baseline: true,
+                ..Default::default()
+            },
+        );
+
+        assert!(result.is_ok(), "expected success");
+        let result = result.unwrap();
+        assert!(result.tps > 0.);
+        assert!(result.soft_tps.is_some_and(|tps| tps > 0.));
+
+        println!("{result}");
+    }
+
     #[test]
     fn test_encryption() {
         let key = hex!("cc4a401b59245e80b1ccc86d4eea62322b04b0c890488a5a53e7306c2e46517d");
@@ -511,4 +698,40 @@ mod tests {
         assert_eq!(HASH_SIZE as u64, result.unwrap());
         assert_eq!(hash_output, hash_expected)
     }
+
+    #[test]
+    fn test_encryption_soft() {
+        let key = hex!("cc4a401b59245e80b1ccc86d4eea62322b04b0c890488a5a53e7306c2e46517d");
+        let data = hex!("42de0be8e330b60d3dca3e5ab4f06f54d53ae89c30060236c41f4984a411ea0b");
+        let enc_expected = hex!("cde6f2e8b795f296d026564f419c86c0c04f173ecba2da93e6100d8a7b04b3c1");
+
+        let cipher = soft::Cipher::with_key(&key);
+
+        let mut enc_output = [0u8; 32];
+        let mut dec_output = [0u8; 32];
+
+        let result = encryption::run_test(&cipher, &data, &mut enc_output, &mut dec_output, None);
+
+        assert!(result.is_ok(), "expected success");
+        assert_eq!(64, result.unwrap());
+        assert_eq!(enc_output, enc_expected);
+        assert_eq!(dec_output, data);
+    }
+
+    #[test]
+    fn test_hash_soft() {
+        let data = hex!("42de0be8e330b60d3dca3e5ab4f06f54d53ae89c30060236c41f4984a411ea0b");
+        let hash_expected =
+            hex!("293ad79b5ee95cfeb84918f4f592f10d280754c6de7ca786cb2f68189e2a8f9e");
+
+        let mut hasher = soft::Hasher::init();
+
+        let mut hash_output = [0u8; HASH_SIZE];
+
+        let result = hash::run_test(&mut hasher, &data, &mut hash_output, None);
+
+        assert!(result.is_ok(), "expected success");
+        assert_eq!(HASH_SIZE as u64, result.unwrap());
+        assert_eq!(hash_output, hash_expected)
+    }
 }
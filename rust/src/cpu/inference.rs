@@ -0,0 +1,689 @@
+use std::{
+    fmt,
+    hint::black_box,
+    ops::{Add, Mul},
+    time::{Duration, Instant},
+};
+
+use rayon::{iter::ParallelIterator, slice::ParallelSliceMut};
+
+use crate::{
+    utils::{Expirable, GetValue, Timeout},
+    CpuFeatures,
+};
+
+/// Width and height of the single-channel input image.
+const INPUT_SIZE: usize = 16;
+const CONV1_CHANNELS: usize = 8;
+const CONV2_CHANNELS: usize = 16;
+const HIDDEN: usize = 64;
+const CLASSES: usize = 10;
+
+/// Fixed-point scale of the `i8` activations and weights, the `f32` network uses the real values.
+const INPUT_SHIFT: u32 = 7;
+const WEIGHT_SHIFT: u32 = 5;
+
+/// Maximum absolute difference between an output probability and the golden vector.
+const TOLERANCE: f32 = 1e-5;
+
+pub(crate) fn bench(_features: &CpuFeatures, config: Config) -> Result<Report, Error> {
+    let mut context = Context::new(config);
+    let mut report_builder = ReportBuilder::new();
+
+    let mut start: Instant;
+    while !context.timeout.reached() {
+        start = Instant::now();
+        let ops = black_box(context.i8.run_test(Some(&context.timeout)));
+
+        report_builder.add_i8(start.elapsed(), ops);
+
+        if ops.is_ok() {
+            context.i8.verify()?;
+        }
+
+        if context.timeout.reached() {
+            break;
+        }
+
+        start = Instant::now();
+        let ops = black_box(context.f32.run_test(Some(&context.timeout)));
+
+        report_builder.add_f32(start.elapsed(), ops);
+
+        if ops.is_ok() {
+            context.f32.verify()?;
+        }
+    }
+
+    Ok(report_builder.build())
+}
+
+pub(crate) fn bench_multithread(features: &CpuFeatures, config: Config) -> Result<Report, Error> {
+    let mut context = Context::new(config);
+    let threadpool = rayon::ThreadPoolBuilder::new()
+        .num_threads(features.num_cores)
+        .build()
+        .unwrap();
+    let mut report_builder = ReportBuilder::new();
+
+    let mut start: Instant;
+    while !context.timeout.reached() {
+        start = Instant::now();
+        let ops = black_box(
+            context
+                .i8
+                .run_test_multithread(&threadpool, Some(&context.timeout)),
+        );
+
+        report_builder.add_i8(start.elapsed(), ops);
+
+        if ops.is_ok() {
+            context.i8.verify()?;
+        }
+
+        if context.timeout.reached() {
+            break;
+        }
+
+        start = Instant::now();
+        let ops = black_box(
+            context
+                .f32
+                .run_test_multithread(&threadpool, Some(&context.timeout)),
+        );
+
+        report_builder.add_f32(start.elapsed(), ops);
+
+        if ops.is_ok() {
+            context.f32.verify()?;
+        }
+    }
+
+    Ok(report_builder.build())
+}
+
+/// Generates the embedded weights, uniformly distributed in `-32..32`.
+const fn weights<const N: usize>(seed: u64) -> [i8; N] {
+    let mut state = seed;
+    let mut weights = [0; N];
+    let mut i = 0;
+    while i < N {
+        // xorshift64
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        weights[i] = (state >> 56) as u8 as i8 >> 2;
+        i += 1;
+    }
+
+    weights
+}
+
+/// Generates the embedded input, a diagonal gradient in `0..128`.
+const fn input() -> [i8; INPUT_SIZE * INPUT_SIZE] {
+    let mut input = [0; INPUT_SIZE * INPUT_SIZE];
+    let mut i = 0;
+    while i < input.len() {
+        let (y, x) = (i / INPUT_SIZE, i % INPUT_SIZE);
+        input[i] = ((y * 7 + x * 13) % 128) as i8;
+        i += 1;
+    }
+
+    input
+}
+
+static INPUT: [i8; INPUT_SIZE * INPUT_SIZE] = input();
+
+static CONV1_WEIGHTS: [i8; CONV1_CHANNELS * 9] = weights(0x9e37_79b9_7f4a_7c15);
+static CONV1_BIAS: [i8; CONV1_CHANNELS] = weights(0xbf58_476d_1ce4_e5b9);
+static CONV2_WEIGHTS: [i8; CONV2_CHANNELS * CONV1_CHANNELS * 9] = weights(0x94d0_49bb_1331_11eb);
+static CONV2_BIAS: [i8; CONV2_CHANNELS] = weights(0x2545_f491_4f6c_dd1d);
+static FC1_WEIGHTS: [i8; HIDDEN * CONV2_CHANNELS * 8 * 8] = weights(0xd6e8_feb8_6659_fd93);
+static FC1_BIAS: [i8; HIDDEN] = weights(0xa076_1d64_78bd_642f);
+static FC2_WEIGHTS: [i8; CLASSES * HIDDEN] = weights(0xe703_7ed1_a0b4_28db);
+static FC2_BIAS: [i8; CLASSES] = weights(0x8ebc_6af0_9c88_c6e3);
+
+/// Element type of the network, activations are accumulated in [Element::Acc].
+trait Element: Copy + Default + Send + Sync {
+    type Acc: Copy + Default + Send + Sync + Add<Output = Self::Acc> + Mul<Output = Self::Acc>;
+
+    /// Expected output probabilities of the embedded network for the embedded input.
+    const GOLDEN: [f32; CLASSES];
+
+    fn from_i8(value: i8, shift: u32) -> Self;
+    fn bias(value: i8, shift: u32) -> Self::Acc;
+    fn widen(self) -> Self::Acc;
+    /// ReLU followed by the conversion back to the element type.
+    fn activate(acc: Self::Acc, shift: u32) -> Self;
+    fn dequantize(acc: Self::Acc, shift: u32) -> f32;
+
+    fn mismatch(index: usize) -> Error;
+}
+
+impl Element for i8 {
+    type Acc = i32;
+
+    const GOLDEN: [f32; CLASSES] = [
+        0.21989822,
+        0.05935845,
+        0.1188291,
+        0.09184643,
+        0.08309797,
+        0.08348434,
+        0.1308592,
+        0.111629605,
+        0.049826436,
+        0.051170185,
+    ];
+
+    fn from_i8(value: i8, _shift: u32) -> Self {
+        value
+    }
+
+    fn bias(value: i8, shift: u32) -> Self::Acc {
+        (value as i32) << shift
+    }
+
+    fn widen(self) -> Self::Acc {
+        self as i32
+    }
+
+    fn activate(acc: Self::Acc, shift: u32) -> Self {
+        (acc >> shift).clamp(0, i8::MAX as i32) as i8
+    }
+
+    fn dequantize(acc: Self::Acc, shift: u32) -> f32 {
+        acc as f32 / scale(shift + INPUT_SHIFT)
+    }
+
+    fn mismatch(index: usize) -> Error {
+        Error::I8Mismatch(index)
+    }
+}
+
+impl Element for f32 {
+    type Acc = f32;
+
+    const GOLDEN: [f32; CLASSES] = [
+        0.22087441,
+        0.058579177,
+        0.12054668,
+        0.09043046,
+        0.082798086,
+        0.083097875,
+        0.130694,
+        0.11371903,
+        0.04883269,
+        0.050427534,
+    ];
+
+    fn from_i8(value: i8, shift: u32) -> Self {
+        value as f32 / scale(shift)
+    }
+
+    fn bias(value: i8, shift: u32) -> Self::Acc {
+        value as f32 / scale(INPUT_SHIFT + WEIGHT_SHIFT - shift)
+    }
+
+    fn widen(self) -> Self::Acc {
+        self
+    }
+
+    fn activate(acc: Self::Acc, shift: u32) -> Self {
+        Self::dequantize(acc, shift).max(0.)
+    }
+
+    fn dequantize(acc: Self::Acc, shift: u32) -> f32 {
+        acc / scale(shift - WEIGHT_SHIFT)
+    }
+
+    fn mismatch(index: usize) -> Error {
+        Error::F32Mismatch(index)
+    }
+}
+
+fn scale(shift: u32) -> f32 {
+    (1 << shift) as f32
+}
+
+/// Fully connected or 3x3 convolution layer, convolutions are lowered to GEMMs with im2col.
+struct Layer<T: Element> {
+    outputs: usize,
+    /// `outputs` x `inputs`, row-major
+    weights: Vec<T>,
+    bias: Vec<T::Acc>,
+    /// Right shift applied to the `i8` accumulators.
+    shift: u32,
+}
+
+impl<T: Element> Layer<T> {
+    fn new(weights: &[i8], bias: &[i8], shift: u32) -> Self {
+        Self {
+            outputs: bias.len(),
+            weights: weights
+                .iter()
+                .map(|&w| T::from_i8(w, WEIGHT_SHIFT))
+                .collect(),
+            // the bias is added to the products of input and weight scales
+            bias: bias.iter().map(|&b| T::bias(b, shift)).collect(),
+            shift,
+        }
+    }
+
+    /// `acc = weights · input + bias`, where `input` is `inputs` x `n`.
+    fn gemm(&self, input: &[T], n: usize, acc: &mut [T::Acc]) {
+        let inputs = self.weights.len() / self.outputs;
+
+        for ((row_w, row_acc), &bias) in self
+            .weights
+            .chunks(inputs)
+            .zip(acc.chunks_mut(n))
+            .zip(&self.bias)
+        {
+            row_acc.fill(bias);
+            for (&w, row_input) in row_w.iter().zip(input.chunks(n)) {
+                let w = w.widen();
+                for (acc, &x) in row_acc.iter_mut().zip(row_input) {
+                    *acc = *acc + w * x.widen();
+                }
+            }
+        }
+    }
+
+    fn forward(&self, input: &[T], n: usize, acc: &mut [T::Acc], output: &mut [T]) {
+        self.gemm(input, n, acc);
+        for (y, &acc) in output.iter_mut().zip(acc.iter()) {
+            *y = T::activate(acc, self.shift);
+        }
+    }
+}
+
+/// Lays out the 3x3 patches of a zero-padded `channels` x `size` x `size` input
+/// as a `channels * 9` x `out_size * out_size` matrix, `channels` is given by the length of `patches`.
+fn im2col<T: Element>(input: &[T], size: usize, stride: usize, patches: &mut [T]) {
+    let out_size = (size - 1) / stride + 1;
+
+    for (row, patch) in patches.chunks_mut(out_size * out_size).enumerate() {
+        let (c, ky, kx) = (row / 9, row % 9 / 3, row % 3);
+        let channel = &input[c * size * size..][..size * size];
+
+        for (i, x) in patch.iter_mut().enumerate() {
+            let y_in = (i / out_size * stride + ky).wrapping_sub(1);
+            let x_in = (i % out_size * stride + kx).wrapping_sub(1);
+
+            *x = if y_in < size && x_in < size {
+                channel[y_in * size + x_in]
+            } else {
+                T::default()
+            };
+        }
+    }
+}
+
+fn softmax(logits: &[f32], probabilities: &mut [f32]) {
+    let max = logits.iter().copied().fold(f32::MIN, f32::max);
+
+    let mut sum = 0.;
+    for (p, &logit) in probabilities.iter_mut().zip(logits) {
+        *p = (logit - max).exp();
+        sum += *p;
+    }
+    probabilities.iter_mut().for_each(|p| *p /= sum);
+}
+
+/// conv 3x3 (1 -> 8) -> ReLU -> conv 3x3 stride 2 (8 -> 16) -> ReLU
+/// -> dense (1024 -> 64) -> ReLU -> dense (64 -> 10) -> softmax
+struct Network<T: Element> {
+    conv1: Layer<T>,
+    conv2: Layer<T>,
+    fc1: Layer<T>,
+    fc2: Layer<T>,
+}
+
+impl<T: Element> Network<T> {
+    fn new() -> Self {
+        Self {
+            conv1: Layer::new(&CONV1_WEIGHTS, &CONV1_BIAS, 7),
+            conv2: Layer::new(&CONV2_WEIGHTS, &CONV2_BIAS, 8),
+            fc1: Layer::new(&FC1_WEIGHTS, &FC1_BIAS, 9),
+            fc2: Layer::new(&FC2_WEIGHTS, &FC2_BIAS, 5),
+        }
+    }
+
+    fn infer(&self, input: &[T], buffers: &mut Buffers<T>, probabilities: &mut [f32]) {
+        const CONV1_N: usize = INPUT_SIZE * INPUT_SIZE;
+        const CONV2_N: usize = (INPUT_SIZE / 2) * (INPUT_SIZE / 2);
+
+        im2col(input, INPUT_SIZE, 1, &mut buffers.patches[..9 * CONV1_N]);
+        self.conv1.forward(
+            &buffers.patches[..9 * CONV1_N],
+            CONV1_N,
+            &mut buffers.acc,
+            &mut buffers.conv1,
+        );
+
+        im2col(
+            &buffers.conv1,
+            INPUT_SIZE,
+            2,
+            &mut buffers.patches[..CONV1_CHANNELS * 9 * CONV2_N],
+        );
+        self.conv2.forward(
+            &buffers.patches[..CONV1_CHANNELS * 9 * CONV2_N],
+            CONV2_N,
+            &mut buffers.acc,
+            &mut buffers.conv2,
+        );
+
+        self.fc1
+            .forward(&buffers.conv2, 1, &mut buffers.acc, &mut buffers.hidden);
+
+        self.fc2
+            .gemm(&buffers.hidden, 1, &mut buffers.acc[..CLASSES]);
+        let mut logits = [0.; CLASSES];
+        for (logit, &acc) in logits.iter_mut().zip(&buffers.acc[..CLASSES]) {
+            *logit = T::dequantize(acc, self.fc2.shift);
+        }
+
+        softmax(&logits, probabilities);
+    }
+}
+
+/// Intermediate activations of a single inference.
+struct Buffers<T: Element> {
+    patches: Vec<T>,
+    acc: Vec<T::Acc>,
+    conv1: Vec<T>,
+    conv2: Vec<T>,
+    hidden: Vec<T>,
+}
+
+impl<T: Element> Buffers<T> {
+    fn new() -> Self {
+        let conv1_len = CONV1_CHANNELS * INPUT_SIZE * INPUT_SIZE;
+
+        Self {
+            patches: vec![T::default(); 9 * conv1_len],
+            acc: vec![T::Acc::default(); conv1_len],
+            conv1: vec![T::default(); conv1_len],
+            conv2: vec![T::default(); CONV2_CHANNELS * (INPUT_SIZE / 2) * (INPUT_SIZE / 2)],
+            hidden: vec![T::default(); HIDDEN],
+        }
+    }
+}
+
+struct Inferences<T: Element> {
+    network: Network<T>,
+    input: Vec<T>,
+    /// Output probabilities of each inference in the batch.
+    outputs: Vec<f32>,
+}
+
+impl<T: Element> Inferences<T> {
+    fn new(batch: usize) -> Self {
+        Self {
+            network: Network::new(),
+            input: INPUT.iter().map(|&x| T::from_i8(x, INPUT_SHIFT)).collect(),
+            outputs: vec![0.; batch * CLASSES],
+        }
+    }
+
+    fn run_test(&mut self, timeout: Option<&Timeout>) -> Result<u64, u64> {
+        let mut buffers = Buffers::new();
+
+        let mut ops = 0;
+        for probabilities in self.outputs.chunks_mut(CLASSES) {
+            timeout.reached_with_err(ops)?;
+
+            self.network.infer(&self.input, &mut buffers, probabilities);
+            ops += 1;
+        }
+
+        Ok(ops)
+    }
+
+    fn run_test_multithread(
+        &mut self,
+        threadpool: &rayon::ThreadPool,
+        timeout: Option<&Timeout>,
+    ) -> Result<u64, u64> {
+        let network = &self.network;
+        let input = &self.input[..];
+
+        threadpool.install(|| {
+            self.outputs
+                .par_chunks_mut(CLASSES)
+                .map_init(Buffers::new, |buffers, probabilities| {
+                    if timeout.reached() {
+                        return Err(0);
+                    }
+
+                    network.infer(input, buffers, probabilities);
+                    Ok(1)
+                })
+                .reduce(
+                    || Ok(0),
+                    |acc, next| match (acc, next) {
+                        (Ok(acc), Ok(next)) => Ok(acc + next),
+                        _ => Err(acc.value() + next.value()),
+                    },
+                )
+        })
+    }
+
+    fn verify(&self) -> Result<(), Error> {
+        for (i, probabilities) in self.outputs.chunks(CLASSES).enumerate() {
+            let matches = probabilities
+                .iter()
+                .zip(&T::GOLDEN)
+                .all(|(p, golden)| (p - golden).abs() <= TOLERANCE);
+            if !matches {
+                return Err(T::mismatch(i));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub struct Config {
+    pub duration: Duration,
+
+    /// Number of inferences per iteration.
+    pub batch: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            duration: Duration::from_secs(10),
+            batch: 64,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    I8Mismatch(usize),
+    F32Mismatch(usize),
+}
+
+pub struct Report {
+    pub i8_duration: Duration,
+    pub i8_inferences: u64,
+    pub i8_tps: f64,
+
+    pub f32_duration: Duration,
+    pub f32_inferences: u64,
+    pub f32_tps: f64,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "inference ... int8 {} inferences/s, f32 {} inferences/s",
+            self.i8_tps.floor(),
+            self.f32_tps.floor()
+        )
+    }
+}
+
+struct ReportBuilder {
+    i8_duration: Duration,
+    i8_inferences: u64,
+
+    f32_duration: Duration,
+    f32_inferences: u64,
+}
+
+impl ReportBuilder {
+    fn new() -> Self {
+        Self {
+            i8_duration: Duration::ZERO,
+            i8_inferences: 0,
+            f32_duration: Duration::ZERO,
+            f32_inferences: 0,
+        }
+    }
+
+    fn add_i8(&mut self, duration: Duration, result: Result<u64, u64>) {
+        self.i8_duration += duration;
+        self.i8_inferences += result.value();
+    }
+
+    fn add_f32(&mut self, duration: Duration, result: Result<u64, u64>) {
+        self.f32_duration += duration;
+        self.f32_inferences += result.value();
+    }
+
+    fn build(self) -> Report {
+        Report {
+            i8_duration: self.i8_duration,
+            i8_inferences: self.i8_inferences,
+            i8_tps: tps(self.i8_duration, self.i8_inferences),
+            f32_duration: self.f32_duration,
+            f32_inferences: self.f32_inferences,
+            f32_tps: tps(self.f32_duration, self.f32_inferences),
+        }
+    }
+}
+
+fn tps(duration: Duration, ops: u64) -> f64 {
+    match duration {
+        Duration::ZERO => 0.,
+        _ => ops as f64 / duration.as_secs_f64(),
+    }
+}
+
+struct Context {
+    i8: Inferences<i8>,
+    f32: Inferences<f32>,
+
+    timeout: Timeout,
+}
+
+impl Context {
+    fn new(config: Config) -> Self {
+        let batch = config.batch.max(1);
+
+        Self {
+            i8: Inferences::new(batch),
+            f32: Inferences::new(batch),
+            timeout: Timeout::new(config.duration),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bench() {
+        let result = bench(
+            &CpuFeatures::default(),
+            Config {
+                duration: Duration::from_secs(1),
+                batch: 4,
+            },
+        );
+
+        assert!(result.is_ok(), "expected success");
+        let result = result.unwrap();
+        assert!(result.i8_tps > 0.);
+        assert!(result.f32_tps > 0.);
+
+        println!("{result}");
+    }
+
+    #[test]
+    fn test_bench_multithread() {
+        let result = bench_multithread(
+            &CpuFeatures {
+                num_cores: 8,
+                ..Default::default()
+            },
+            Config {
+                duration: Duration::from_secs(1),
+                batch: 16,
+            },
+        );
+
+        assert!(result.is_ok(), "expected success");
+        let result = result.unwrap();
+        assert!(result.i8_tps > 0.);
+        assert!(result.f32_tps > 0.);
+
+        println!("{result}");
+    }
+
+    #[test]
+    fn test_golden() {
+        let mut i8 = Inferences::<i8>::new(2);
+        assert_eq!(Ok(2), i8.run_test(None));
+        assert!(i8.verify().is_ok(), "expected int8 output to match");
+
+        let mut f32 = Inferences::<f32>::new(2);
+        assert_eq!(Ok(2), f32.run_test(None));
+        assert!(f32.verify().is_ok(), "expected f32 output to match");
+
+        f32.outputs[CLASSES] += 0.01;
+        assert!(matches!(f32.verify(), Err(Error::F32Mismatch(1))));
+    }
+
+    #[test]
+    fn test_im2col() {
+        let input: Vec<i8> = (1..=16).collect();
+        let mut patches = vec![0; 9 * 4];
+        im2col(&input, 4, 2, &mut patches);
+
+        #[rustfmt::skip]
+        let expected: Vec<i8> = vec![
+            0, 0, 0, 6,
+            0, 0, 5, 7,
+            0, 0, 6, 8,
+            0, 2, 0, 10,
+            1, 3, 9, 11,
+            2, 4, 10, 12,
+            0, 6, 0, 14,
+            5, 7, 13, 15,
+            6, 8, 14, 16,
+        ];
+        assert_eq!(expected, patches);
+    }
+
+    #[test]
+    fn test_gemm() {
+        let layer = Layer::<f32> {
+            outputs: 2,
+            weights: vec![1., 2., 3., 4.],
+            bias: vec![0.5, -0.5],
+            shift: WEIGHT_SHIFT,
+        };
+        let mut acc = vec![0.; 6];
+        layer.gemm(&[1., 2., 3., 4., 5., 6.], 3, &mut acc);
+
+        assert_eq!(vec![9.5, 12.5, 15.5, 18.5, 25.5, 32.5], acc);
+    }
+}
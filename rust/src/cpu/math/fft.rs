@@ -0,0 +1,669 @@
+use std::{
+    f64::consts::PI,
+    fmt,
+    hint::black_box,
+    ops::{Add, Mul, Neg, Sub},
+    time::{Duration, Instant},
+};
+
+use rand::Rng;
+use rayon::{iter::ParallelIterator, slice::ParallelSliceMut};
+
+use crate::{
+    utils::{Expirable, GetValue, Timeout},
+    CpuFeatures,
+};
+
+pub(crate) fn bench(config: Config) -> Result<Report, Error> {
+    match config.precision {
+        Precision::F32 => run::<f32>(config, |plan, context| {
+            plan.run_test(&mut context.data[..], Some(&context.timeout))
+        }),
+        Precision::F64 => run::<f64>(config, |plan, context| {
+            plan.run_test(&mut context.data[..], Some(&context.timeout))
+        }),
+    }
+}
+
+pub(crate) fn bench_multithread(features: &CpuFeatures, config: Config) -> Result<Report, Error> {
+    let threadpool = rayon::ThreadPoolBuilder::new()
+        .num_threads(features.num_cores)
+        .build()
+        .unwrap();
+
+    match config.precision {
+        Precision::F32 => run::<f32>(config, |plan, context| {
+            plan.run_test_multithread(&threadpool, &mut context.data[..], Some(&context.timeout))
+        }),
+        Precision::F64 => run::<f64>(config, |plan, context| {
+            plan.run_test_multithread(&threadpool, &mut context.data[..], Some(&context.timeout))
+        }),
+    }
+}
+
+fn run<F: Float>(
+    config: Config,
+    forward: impl Fn(&Plan<F>, &mut Context<F>) -> Result<u64, u64>,
+) -> Result<Report, Error> {
+    let mut context = Context::new(config);
+    let plan = Plan::new(context.n);
+    let mut report_builder = ReportBuilder::new(context.precision, context.n, plan.algorithm);
+
+    let mut start: Instant;
+    while !context.timeout.reached() {
+        context.reset_data();
+
+        start = Instant::now();
+        let ops = black_box(forward(&plan, &mut context));
+
+        report_builder.add(start.elapsed(), ops);
+
+        if ops.is_ok() {
+            context.verify_round_trip(&plan)?;
+        }
+    }
+
+    Ok(report_builder.build())
+}
+
+/// Estimated floating point operations of a complex transform of size `n`.
+fn flops(n: usize) -> f64 {
+    5. * n as f64 * (n as f64).log2()
+}
+
+trait Float:
+    Copy
+    + Default
+    + Send
+    + Sync
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Neg<Output = Self>
+{
+    /// Maximum absolute error accepted after a round trip.
+    const TOLERANCE: f64;
+
+    fn from_f64(value: f64) -> Self;
+    fn to_f64(self) -> f64;
+}
+
+impl Float for f32 {
+    const TOLERANCE: f64 = 1e-3;
+
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+}
+
+impl Float for f64 {
+    const TOLERANCE: f64 = 1e-9;
+
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+
+    fn to_f64(self) -> f64 {
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct Complex<F> {
+    re: F,
+    im: F,
+}
+
+impl<F: Float> Complex<F> {
+    fn new(re: F, im: F) -> Self {
+        Self { re, im }
+    }
+
+    /// `e^(-2πi * k / n)`
+    fn root(k: usize, n: usize) -> Self {
+        let angle = -2. * PI * k as f64 / n as f64;
+
+        Self::new(F::from_f64(angle.cos()), F::from_f64(angle.sin()))
+    }
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.re + rhs.re, self.im + rhs.im)
+    }
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.re - rhs.re, self.im - rhs.im)
+    }
+
+    fn mul(self, rhs: Self) -> Self {
+        Self::new(
+            self.re * rhs.re - self.im * rhs.im,
+            self.re * rhs.im + self.im * rhs.re,
+        )
+    }
+
+    fn scale(self, factor: F) -> Self {
+        Self::new(self.re * factor, self.im * factor)
+    }
+
+    fn conj(self) -> Self {
+        Self::new(self.re, -self.im)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    Radix2,
+    MixedRadix,
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Algorithm::Radix2 => write!(f, "radix-2"),
+            Algorithm::MixedRadix => write!(f, "mixed-radix"),
+        }
+    }
+}
+
+struct Plan<F> {
+    n: usize,
+    algorithm: Algorithm,
+    /// `e^(-2πi * k / n)` for `k` in `0..n`
+    twiddles: Vec<Complex<F>>,
+    /// Radix of each decimation step, only used by [Algorithm::MixedRadix].
+    factors: Vec<usize>,
+}
+
+impl<F: Float> Plan<F> {
+    fn new(n: usize) -> Self {
+        let algorithm = if n.is_power_of_two() {
+            Algorithm::Radix2
+        } else {
+            Algorithm::MixedRadix
+        };
+
+        Self {
+            n,
+            algorithm,
+            twiddles: (0..n).map(|k| Complex::root(k, n)).collect(),
+            factors: factorize(n),
+        }
+    }
+
+    fn run_test(&self, data: &mut [Complex<F>], timeout: Option<&Timeout>) -> Result<u64, u64> {
+        let mut scratch = Scratch::new(self);
+
+        let mut ops = 0;
+        for signal in data.chunks_mut(self.n) {
+            timeout.reached_with_err(ops)?;
+
+            self.forward(signal, &mut scratch);
+            ops += 1;
+        }
+
+        Ok(ops)
+    }
+
+    fn run_test_multithread(
+        &self,
+        threadpool: &rayon::ThreadPool,
+        data: &mut [Complex<F>],
+        timeout: Option<&Timeout>,
+    ) -> Result<u64, u64> {
+        threadpool.install(|| {
+            data.par_chunks_mut(self.n)
+                .map_init(
+                    || Scratch::new(self),
+                    |scratch, signal| {
+                        if timeout.reached() {
+                            return Err(0);
+                        }
+
+                        self.forward(signal, scratch);
+                        Ok(1)
+                    },
+                )
+                .reduce(
+                    || Ok(0),
+                    |acc, next| match (acc, next) {
+                        (Ok(acc), Ok(next)) => Ok(acc + next),
+                        _ => Err(acc.value() + next.value()),
+                    },
+                )
+        })
+    }
+
+    fn forward(&self, signal: &mut [Complex<F>], scratch: &mut Scratch<F>) {
+        match self.algorithm {
+            Algorithm::Radix2 => self.radix2(signal),
+            Algorithm::MixedRadix => {
+                scratch.input.copy_from_slice(signal);
+                self.mixed_radix(
+                    signal,
+                    &scratch.input[..],
+                    1,
+                    &self.factors[..],
+                    &mut scratch.butterfly[..],
+                );
+            }
+        }
+    }
+
+    /// Computed as `conj(forward(conj(x))) / n`.
+    fn inverse(&self, signal: &mut [Complex<F>], scratch: &mut Scratch<F>) {
+        signal.iter_mut().for_each(|x| *x = x.conj());
+        self.forward(signal, scratch);
+
+        let factor = F::from_f64(1. / self.n as f64);
+        signal.iter_mut().for_each(|x| *x = x.conj().scale(factor));
+    }
+
+    /// In-place iterative Cooley-Tukey.
+    fn radix2(&self, signal: &mut [Complex<F>]) {
+        let n = signal.len();
+        if n < 2 {
+            return;
+        }
+        let log_n = n.trailing_zeros();
+
+        for i in 0..n {
+            let j = i.reverse_bits() >> (usize::BITS - log_n);
+            if i < j {
+                signal.swap(i, j);
+            }
+        }
+
+        let mut len = 2;
+        while len <= n {
+            let half = len / 2;
+            let step = n / len;
+            for block in signal.chunks_mut(len) {
+                let (lo, hi) = block.split_at_mut(half);
+                for (k, (u, v)) in lo.iter_mut().zip(hi.iter_mut()).enumerate() {
+                    let t = v.mul(self.twiddles[k * step]);
+                    *v = u.sub(t);
+                    *u = u.add(t);
+                }
+            }
+            len *= 2;
+        }
+    }
+
+    /// Recursive decimation in time, reads `input` with `stride`
+    /// and combines the sub-transforms with a generic radix-p butterfly.
+    fn mixed_radix(
+        &self,
+        output: &mut [Complex<F>],
+        input: &[Complex<F>],
+        stride: usize,
+        factors: &[usize],
+        scratch: &mut [Complex<F>],
+    ) {
+        let p = factors[0];
+        let m = output.len() / p;
+
+        if m == 1 {
+            for (q, x) in output.iter_mut().enumerate() {
+                *x = input[q * stride];
+            }
+        } else {
+            for (q, sub) in output.chunks_mut(m).enumerate() {
+                self.mixed_radix(
+                    sub,
+                    &input[q * stride..],
+                    stride * p,
+                    &factors[1..],
+                    scratch,
+                );
+            }
+        }
+
+        for u in 0..m {
+            for (q, x) in scratch[..p].iter_mut().enumerate() {
+                *x = output[q * m + u];
+            }
+
+            for q1 in 0..p {
+                let k = q1 * m + u;
+                let mut sum = scratch[0];
+                let mut twiddle = 0;
+                for x in &scratch[1..p] {
+                    twiddle = (twiddle + stride * k) % self.n;
+                    sum = sum.add(x.mul(self.twiddles[twiddle]));
+                }
+                output[k] = sum;
+            }
+        }
+    }
+}
+
+/// Per-thread buffers used by [Algorithm::MixedRadix].
+struct Scratch<F> {
+    input: Vec<Complex<F>>,
+    butterfly: Vec<Complex<F>>,
+}
+
+impl<F: Float> Scratch<F> {
+    fn new(plan: &Plan<F>) -> Self {
+        match plan.algorithm {
+            Algorithm::Radix2 => Self {
+                input: Vec::new(),
+                butterfly: Vec::new(),
+            },
+            Algorithm::MixedRadix => Self {
+                input: vec![Complex::default(); plan.n],
+                butterfly: vec![
+                    Complex::default();
+                    plan.factors.iter().max().copied().unwrap_or(1)
+                ],
+            },
+        }
+    }
+}
+
+/// Splits `n` into radices, preferring 4 and 2 over larger primes.
+fn factorize(mut n: usize) -> Vec<usize> {
+    let mut factors = Vec::new();
+    let mut p = 4;
+    while n > 1 {
+        while n % p != 0 {
+            p = match p {
+                4 => 2,
+                2 => 3,
+                _ => p + 2,
+            };
+            if p * p > n {
+                p = n;
+            }
+        }
+        factors.push(p);
+        n /= p;
+    }
+
+    factors
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Precision {
+    #[default]
+    F32,
+    F64,
+}
+
+impl fmt::Display for Precision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Precision::F32 => write!(f, "f32"),
+            Precision::F64 => write!(f, "f64"),
+        }
+    }
+}
+
+pub struct Config {
+    pub rng: Box<dyn rand::RngCore>,
+
+    pub duration: Duration,
+
+    /// Transform size, powers of two use the radix-2 algorithm.
+    pub n: usize,
+    /// Number of transforms per iteration.
+    pub batch: usize,
+    pub precision: Precision,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            rng: Box::new(rand::thread_rng()),
+            duration: Duration::from_secs(10),
+            n: 4096,
+            batch: 64,
+            precision: Precision::default(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    RoundTrip(usize),
+}
+
+pub struct Report {
+    pub precision: Precision,
+    pub n: usize,
+    pub algorithm: Algorithm,
+    pub duration: Duration,
+    pub transforms: u64,
+    pub tps: f64,
+    pub gflops: f64,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "fft ({}, n = {}, {}) ... {} transforms/s, {:.2} GFLOPS",
+            self.precision,
+            self.n,
+            self.algorithm,
+            self.tps.floor(),
+            self.gflops
+        )
+    }
+}
+
+struct ReportBuilder {
+    precision: Precision,
+    n: usize,
+    algorithm: Algorithm,
+    duration: Duration,
+    transforms: u64,
+}
+
+impl ReportBuilder {
+    fn new(precision: Precision, n: usize, algorithm: Algorithm) -> Self {
+        Self {
+            precision,
+            n,
+            algorithm,
+            duration: Duration::ZERO,
+            transforms: 0,
+        }
+    }
+
+    fn add(&mut self, duration: Duration, result: Result<u64, u64>) {
+        self.duration += duration;
+        self.transforms += result.value();
+    }
+
+    fn build(self) -> Report {
+        let tps = match self.duration {
+            Duration::ZERO => 0.,
+            _ => self.transforms as f64 / self.duration.as_secs_f64(),
+        };
+
+        Report {
+            precision: self.precision,
+            n: self.n,
+            algorithm: self.algorithm,
+            duration: self.duration,
+            transforms: self.transforms,
+            tps,
+            gflops: tps * flops(self.n) / 1e9,
+        }
+    }
+}
+
+struct Context<F> {
+    rng: Box<dyn rand::RngCore>,
+
+    precision: Precision,
+    n: usize,
+    input: Vec<Complex<F>>,
+    data: Vec<Complex<F>>,
+
+    timeout: Timeout,
+}
+
+impl<F: Float> Context<F> {
+    fn new(config: Config) -> Self {
+        let n = config.n.max(1);
+        let len = n * config.batch.max(1);
+
+        Self {
+            rng: config.rng,
+            precision: config.precision,
+            n,
+            input: vec![Complex::default(); len],
+            data: vec![Complex::default(); len],
+            timeout: Timeout::new(config.duration),
+        }
+    }
+
+    fn reset_data(&mut self) {
+        for x in self.input.iter_mut() {
+            *x = Complex::new(
+                F::from_f64(self.rng.gen_range(-1.0..1.0)),
+                F::from_f64(self.rng.gen_range(-1.0..1.0)),
+            );
+        }
+        self.data.copy_from_slice(&self.input);
+    }
+
+    /// Transforms a random signal of the batch back and compares it with the input.
+    fn verify_round_trip(&mut self, plan: &Plan<F>) -> Result<(), Error> {
+        let batch = self.data.len() / self.n;
+        let i = self.rng.gen_range(0..batch);
+
+        let signal = &mut self.data[i * self.n..][..self.n];
+        plan.inverse(signal, &mut Scratch::new(plan));
+
+        let input = &self.input[i * self.n..][..self.n];
+        for (x, y) in signal.iter().zip(input) {
+            if (x.re - y.re).to_f64().abs() > F::TOLERANCE
+                || (x.im - y.im).to_f64().abs() > F::TOLERANCE
+            {
+                return Err(Error::RoundTrip(i));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dft<F: Float>(signal: &[Complex<F>]) -> Vec<Complex<F>> {
+        let n = signal.len();
+
+        (0..n)
+            .map(|k| {
+                signal
+                    .iter()
+                    .enumerate()
+                    .fold(Complex::default(), |acc, (j, x)| {
+                        acc.add(x.mul(Complex::root(j * k % n, n)))
+                    })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_bench() {
+        let features = CpuFeatures {
+            num_cores: 2,
+            ..Default::default()
+        };
+
+        for (n, algorithm) in [(256, Algorithm::Radix2), (360, Algorithm::MixedRadix)] {
+            for precision in [Precision::F32, Precision::F64] {
+                let config = || Config {
+                    duration: Duration::from_millis(200),
+                    n,
+                    batch: 8,
+                    precision,
+                    ..Default::default()
+                };
+
+                for result in [bench(config()), bench_multithread(&features, config())] {
+                    assert!(result.is_ok(), "expected success");
+                    let result = result.unwrap();
+                    assert_eq!(algorithm, result.algorithm);
+                    assert!(result.transforms > 0);
+                    assert!(result.gflops > 0.);
+
+                    println!("{result}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_forward() {
+        let mut rng = rand::thread_rng();
+
+        for n in [1, 2, 8, 64, 6, 12, 15, 49, 97] {
+            let plan = Plan::<f64>::new(n);
+            let mut scratch = Scratch::new(&plan);
+
+            let input: Vec<Complex<f64>> = (0..n)
+                .map(|_| Complex::new(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0)))
+                .collect();
+            let expected = dft(&input);
+
+            let mut signal = input.clone();
+            plan.forward(&mut signal, &mut scratch);
+            for (x, y) in signal.iter().zip(&expected) {
+                assert!(
+                    (x.re - y.re).abs() < 1e-9 && (x.im - y.im).abs() < 1e-9,
+                    "n = {n}"
+                );
+            }
+
+            plan.inverse(&mut signal, &mut scratch);
+            for (x, y) in signal.iter().zip(&input) {
+                assert!(
+                    (x.re - y.re).abs() < 1e-9 && (x.im - y.im).abs() < 1e-9,
+                    "n = {n}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_factorize() {
+        assert_eq!(Vec::<usize>::new(), factorize(1));
+        assert_eq!(vec![4, 4, 2], factorize(32));
+        assert_eq!(vec![4, 2, 3, 3, 5], factorize(360));
+        assert_eq!(vec![7, 7], factorize(49));
+        assert_eq!(vec![97], factorize(97));
+    }
+
+    #[test]
+    fn test_verify_round_trip() {
+        let plan = Plan::<f32>::new(16);
+        let mut context = Context::<f32>::new(Config {
+            n: 16,
+            batch: 1,
+            ..Default::default()
+        });
+        context.reset_data();
+
+        plan.run_test(&mut context.data, None).unwrap();
+        assert!(context.verify_round_trip(&plan).is_ok());
+
+        context.reset_data();
+        plan.run_test(&mut context.data, None).unwrap();
+        context.data[3] = context.data[3].add(Complex::new(1., 0.));
+        assert!(matches!(
+            context.verify_round_trip(&plan),
+            Err(Error::RoundTrip(0))
+        ));
+    }
+}
@@ -0,0 +1,1453 @@
+use std::{
+    fmt,
+    hint::black_box,
+    ops::{Add, Mul},
+    time::{Duration, Instant},
+};
+
+use rand::{Fill, Rng};
+use rayon::{
+    iter::{IndexedParallelIterator, ParallelIterator},
+    slice::{ParallelSlice, ParallelSliceMut},
+};
+
+use crate::{
+    utils::{vec_with_len, Expirable, GetValue, Timeout},
+    CpuFeatures,
+};
+
+pub mod fft;
+mod simd;
+
+/// Number of result cells checked after each SIMD multiplication.
+const VERIFY_SAMPLES: usize = 64;
+
+/// Checks the result of a completed multiplication outside the measured time.
+type Verify<T, R> = fn(&mut Context<T, R>) -> Result<(), Error>;
+
+pub(crate) fn bench(features: &CpuFeatures, mut config: Config) -> Result<Report, Error> {
+    let fft = config.fft.take();
+    let element_type = config.element_type;
+    let kernel = Kernel::select(features, element_type);
+
+    let mut report = match element_type {
+        ElementType::I8 if kernel != Kernel::Scalar => bench_typed::<i8, i32>(
+            config,
+            element_type,
+            kernel,
+            |context| simd(kernel, context),
+            Some(verify_simd),
+            tiled,
+        ),
+        ElementType::I8 => {
+            bench_typed::<i8, i32>(config, element_type, kernel, scalar, None, tiled)
+        }
+        ElementType::I16 => {
+            bench_typed::<i16, i64>(config, element_type, kernel, scalar, None, tiled)
+        }
+        ElementType::F32 => {
+            bench_typed::<f32, f32>(config, element_type, kernel, scalar, None, tiled)
+        }
+        ElementType::F64 => {
+            bench_typed::<f64, f64>(config, element_type, kernel, scalar, None, tiled)
+        }
+    }?;
+
+    if let Some(fft) = fft {
+        report.fft = Some(fft::bench(fft).map_err(Error::Fft)?);
+    }
+
+    Ok(report)
+}
+
+pub(crate) fn bench_multithread(
+    features: &CpuFeatures,
+    mut config: Config,
+) -> Result<Report, Error> {
+    let fft = config.fft.take();
+    let element_type = config.element_type;
+    let kernel = Kernel::select(features, element_type);
+    let threadpool = rayon::ThreadPoolBuilder::new()
+        .num_threads(features.num_cores)
+        .build()
+        .unwrap();
+
+    macro_rules! bench_multithread_typed {
+        ($t: ty, $r: ty) => {
+            bench_typed::<$t, $r>(
+                config,
+                element_type,
+                kernel,
+                |context| scalar_multithread(&threadpool, context),
+                None,
+                |context, tiling| tiled_multithread(&threadpool, context, tiling),
+            )
+        };
+    }
+
+    let mut report = match element_type {
+        ElementType::I8 if kernel != Kernel::Scalar => bench_typed::<i8, i32>(
+            config,
+            element_type,
+            kernel,
+            |context| simd_multithread(&threadpool, kernel, context),
+            Some(verify_simd),
+            |context, tiling| tiled_multithread(&threadpool, context, tiling),
+        ),
+        ElementType::I8 => bench_multithread_typed!(i8, i32),
+        ElementType::I16 => bench_multithread_typed!(i16, i64),
+        ElementType::F32 => bench_multithread_typed!(f32, f32),
+        ElementType::F64 => bench_multithread_typed!(f64, f64),
+    }?;
+
+    if let Some(fft) = fft {
+        report.fft = Some(fft::bench_multithread(features, fft).map_err(Error::Fft)?);
+    }
+
+    Ok(report)
+}
+
+fn bench_typed<T, R>(
+    config: Config,
+    element_type: ElementType,
+    kernel: Kernel,
+    mul: impl Fn(&mut Context<T, R>) -> Result<u64, u64>,
+    verify: Option<Verify<T, R>>,
+    mul_tiled: impl Fn(&mut Context<T, R>, &Tiling) -> Result<u64, u64>,
+) -> Result<Report, Error>
+where
+    T: Copy,
+    R: Copy + Default + PartialEq,
+    [T]: Fill,
+{
+    let tiling = config.tiling;
+    let mut context = Context::new(config);
+    let mut report_builder = ReportBuilder::new(element_type, kernel, context.n);
+
+    // the SIMD kernels multiply by rows of a transposed `matrix_b`
+    let transpose_b = kernel != Kernel::Scalar;
+    run(&mut context, &mut report_builder, transpose_b, &mul, verify)?;
+
+    if let Some(tiling) = tiling {
+        context.reset_timeout();
+        let mut tiled_report_builder = ReportBuilder::new(element_type, Kernel::Scalar, context.n);
+
+        run(
+            &mut context,
+            &mut tiled_report_builder,
+            tiling.transpose_b,
+            |context| mul_tiled(context, &tiling),
+            None,
+        )?;
+
+        report_builder.set_tiled(tiling, tiled_report_builder);
+    }
+
+    Ok(report_builder.build())
+}
+
+fn run<T, R>(
+    context: &mut Context<T, R>,
+    report_builder: &mut ReportBuilder,
+    transpose_b: bool,
+    mul: impl Fn(&mut Context<T, R>) -> Result<u64, u64>,
+    verify: Option<Verify<T, R>>,
+) -> Result<(), Error>
+where
+    T: Copy,
+    R: Copy + Default + PartialEq,
+    [T]: Fill,
+{
+    let mut start: Instant;
+    'main: while !context.timeout.reached() {
+        context.reset_matrices();
+        if transpose_b {
+            context.transpose_b();
+        }
+
+        start = Instant::now();
+        let ops = black_box(mul(context));
+
+        report_builder.add(start.elapsed(), ops);
+
+        if ops.is_ok() {
+            for i in 0..context.n {
+                if context.timeout.reached() {
+                    break 'main;
+                }
+
+                if context.matrix_r[i * context.n] /* first in row */ == R::default()
+                    && context.matrix_r[(i + 1) * context.n - 1] /* last in row */ == R::default()
+                {
+                    return Err(Error::Empty);
+                }
+            }
+
+            if let Some(verify) = verify {
+                verify(context)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn scalar<T, R>(context: &mut Context<T, R>) -> Result<u64, u64>
+where
+    T: Into<R> + Copy + Send + Sync,
+    R: Add<Output = R> + Mul<Output = R> + Copy + Send + Sync,
+{
+    matrix::run_test(
+        &context.matrix_a.chunks(context.n).collect::<Vec<_>>()[..],
+        &context.matrix_b.chunks(context.n).collect::<Vec<_>>()[..],
+        &mut context.matrix_r.chunks_mut(context.n).collect::<Vec<_>>()[..],
+        Some(&context.timeout),
+    )
+}
+
+fn scalar_multithread<T, R>(
+    threadpool: &rayon::ThreadPool,
+    context: &mut Context<T, R>,
+) -> Result<u64, u64>
+where
+    T: Into<R> + Copy + Send + Sync,
+    R: Add<Output = R> + Mul<Output = R> + Copy + Send + Sync,
+{
+    matrix::run_test_multithread(
+        threadpool,
+        &context.matrix_a.chunks(context.n).collect::<Vec<_>>()[..],
+        &context.matrix_b.chunks(context.n).collect::<Vec<_>>()[..],
+        &mut context.matrix_r.chunks_mut(context.n).collect::<Vec<_>>()[..],
+        Some(&context.timeout),
+    )
+}
+
+fn simd(kernel: Kernel, context: &mut Context<i8, i32>) -> Result<u64, u64> {
+    simd::run_test(
+        kernel,
+        &context.matrix_a[..],
+        &context.matrix_b[..],
+        &mut context.matrix_r[..],
+        context.n,
+        Some(&context.timeout),
+    )
+}
+
+fn simd_multithread(
+    threadpool: &rayon::ThreadPool,
+    kernel: Kernel,
+    context: &mut Context<i8, i32>,
+) -> Result<u64, u64> {
+    let n = context.n;
+    let matrix_b = &context.matrix_b[..];
+    let timeout = Some(&context.timeout);
+
+    par_row_pairs(
+        threadpool,
+        &context.matrix_a[..],
+        &mut context.matrix_r[..],
+        n,
+        |rows_a, rows_r| simd::run_test(kernel, rows_a, matrix_b, rows_r, n, timeout),
+    )
+}
+
+/// Runs `mul` on pairs of rows in parallel, so that the 2x2 kernels see both rows of a block.
+fn par_row_pairs(
+    threadpool: &rayon::ThreadPool,
+    matrix_a: &[i8],
+    matrix_r: &mut [i32],
+    n: usize,
+    mul: impl Fn(&[i8], &mut [i32]) -> Result<u64, u64> + Sync,
+) -> Result<u64, u64> {
+    threadpool.install(|| {
+        matrix_a
+            .par_chunks(2 * n)
+            .zip(matrix_r.par_chunks_mut(2 * n))
+            .map(|(rows_a, rows_r)| mul(rows_a, rows_r))
+            .reduce(
+                || Ok(0),
+                |acc, next| match (acc, next) {
+                    (Ok(acc), Ok(next)) => Ok(acc + next),
+                    _ => Err(acc.value() + next.value()),
+                },
+            )
+    })
+}
+
+/// Compares a random sample of the result cells with the scalar dot product.
+fn verify_simd(context: &mut Context<i8, i32>) -> Result<(), Error> {
+    let n = context.n;
+    for _ in 0..VERIFY_SAMPLES {
+        let i = context.rng.gen_range(0..n);
+        let j = context.rng.gen_range(0..n);
+
+        // `matrix_b` is transposed
+        let expected = simd::dot(
+            &context.matrix_a[i * n..][..n],
+            &context.matrix_b[j * n..][..n],
+        );
+        if context.matrix_r[i * n + j] != expected {
+            return Err(Error::Mismatch(i, j));
+        }
+    }
+
+    Ok(())
+}
+
+fn tiled<T, R>(context: &mut Context<T, R>, tiling: &Tiling) -> Result<u64, u64>
+where
+    T: Into<R> + Copy + Send + Sync,
+    R: Add<Output = R> + Mul<Output = R> + Copy + Send + Sync,
+{
+    matrix::run_test_tiled(
+        &context.matrix_a[..],
+        &context.matrix_b[..],
+        &mut context.matrix_r[..],
+        context.n,
+        tiling,
+        Some(&context.timeout),
+    )
+}
+
+fn tiled_multithread<T, R>(
+    threadpool: &rayon::ThreadPool,
+    context: &mut Context<T, R>,
+    tiling: &Tiling,
+) -> Result<u64, u64>
+where
+    T: Into<R> + Copy + Send + Sync,
+    R: Add<Output = R> + Mul<Output = R> + Copy + Send + Sync,
+{
+    matrix::run_test_tiled_multithread(
+        threadpool,
+        &context.matrix_a[..],
+        &context.matrix_b[..],
+        &mut context.matrix_r[..],
+        context.n,
+        tiling,
+        Some(&context.timeout),
+    )
+}
+
+mod matrix {
+    use rayon::{
+        iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator},
+        slice::ParallelSliceMut,
+    };
+
+    use crate::utils::{AddValue, GetValue};
+
+    use super::*;
+
+    macro_rules! split {
+        ($matrix: expr, $row_mid: expr, $col_mid: expr) => {{
+            let (e1, e2) = $matrix.split_at($row_mid);
+            let (e11, e12) = e1
+                .iter()
+                .map(|row| row.split_at($col_mid))
+                .collect::<(Vec<_>, Vec<_>)>();
+            let (e21, e22) = e2
+                .iter()
+                .map(|row| row.split_at($col_mid))
+                .collect::<(Vec<_>, Vec<_>)>();
+
+            (e11, e12, e21, e22)
+        }};
+        (mut $matrix: expr, $row_mid: expr, $col_mid: expr) => {{
+            let (e1, e2) = $matrix.split_at_mut($row_mid);
+            let (e11, e12) = e1
+                .iter_mut()
+                .map(|row| row.split_at_mut($col_mid))
+                .collect::<(Vec<_>, Vec<_>)>();
+            let (e21, e22) = e2
+                .iter_mut()
+                .map(|row| row.split_at_mut($col_mid))
+                .collect::<(Vec<_>, Vec<_>)>();
+
+            (e11, e12, e21, e22)
+        }};
+    }
+
+    pub(super) fn run_test<T, R>(
+        matrix_a: &[&[T]],
+        matrix_b: &[&[T]],
+        matrix_r: &mut [&mut [R]],
+        timeout: Option<&Timeout>,
+    ) -> Result<u64, u64>
+    where
+        T: Into<R> + Copy + Send + Sync,
+        R: Add<Output = R> + Mul<Output = R> + Copy + Send + Sync,
+    {
+        mul(matrix_a, matrix_b, matrix_r, timeout, None)
+    }
+
+    pub(super) fn run_test_multithread<T, R>(
+        threadpool: &rayon::ThreadPool,
+        matrix_a: &[&[T]],
+        matrix_b: &[&[T]],
+        matrix_r: &mut [&mut [R]],
+        timeout: Option<&Timeout>,
+    ) -> Result<u64, u64>
+    where
+        T: Into<R> + Copy + Send + Sync,
+        R: Add<Output = R> + Mul<Output = R> + Copy + Send + Sync,
+    {
+        threadpool.install(|| mul(matrix_a, matrix_b, matrix_r, timeout, Some(&threadpool)))
+    }
+
+    pub(super) fn run_test_tiled<T, R>(
+        matrix_a: &[T],
+        matrix_b: &[T],
+        matrix_r: &mut [R],
+        n: usize,
+        tiling: &Tiling,
+        timeout: Option<&Timeout>,
+    ) -> Result<u64, u64>
+    where
+        T: Into<R> + Copy + Send + Sync,
+        R: Add<Output = R> + Mul<Output = R> + Copy + Send + Sync,
+    {
+        mul_tiled(matrix_a, matrix_b, matrix_r, 0, n, tiling, timeout)
+    }
+
+    pub(super) fn run_test_tiled_multithread<T, R>(
+        threadpool: &rayon::ThreadPool,
+        matrix_a: &[T],
+        matrix_b: &[T],
+        matrix_r: &mut [R],
+        n: usize,
+        tiling: &Tiling,
+        timeout: Option<&Timeout>,
+    ) -> Result<u64, u64>
+    where
+        T: Into<R> + Copy + Send + Sync,
+        R: Add<Output = R> + Mul<Output = R> + Copy + Send + Sync,
+    {
+        let tile_size = tiling.tile_size.max(1);
+
+        threadpool.install(|| {
+            matrix_r
+                .par_chunks_mut(tile_size * n)
+                .enumerate()
+                .map(|(block, rows)| {
+                    if timeout.reached() {
+                        return Err(0);
+                    }
+
+                    mul_tiled(
+                        matrix_a,
+                        matrix_b,
+                        rows,
+                        block * tile_size,
+                        n,
+                        tiling,
+                        timeout,
+                    )
+                })
+                .reduce(
+                    || Ok(0),
+                    |acc, next| match (acc, next) {
+                        (Ok(acc), Ok(next)) => Ok(acc + next),
+                        _ => Err(acc.value() + next.value()),
+                    },
+                )
+        })
+    }
+
+    /// Multiplies the rows of `matrix_a` starting at `row_start` by `matrix_b`
+    /// into `matrix_r`, one `tile_size`-sized block at a time.
+    /// If `tiling.transpose_b` is set, `matrix_b` is expected to be already transposed.
+    fn mul_tiled<T, R>(
+        matrix_a: &[T],
+        matrix_b: &[T],
+        matrix_r: &mut [R],
+        row_start: usize,
+        n: usize,
+        tiling: &Tiling,
+        timeout: Option<&Timeout>,
+    ) -> Result<u64, u64>
+    where
+        T: Into<R> + Copy,
+        R: Add<Output = R> + Mul<Output = R> + Copy,
+    {
+        let tile_size = tiling.tile_size.max(1);
+        let rows = matrix_r.len() / n;
+
+        let mut ops = 0;
+        for ii in (0..rows).step_by(tile_size) {
+            let i_end = usize::min(ii + tile_size, rows);
+            for kk in (0..n).step_by(tile_size) {
+                let k_end = usize::min(kk + tile_size, n);
+                for jj in (0..n).step_by(tile_size) {
+                    timeout.reached_with_err(ops)?;
+                    let j_end = usize::min(jj + tile_size, n);
+
+                    for i in ii..i_end {
+                        let row_a = &matrix_a[(row_start + i) * n..][kk..k_end];
+                        let row_r = &mut matrix_r[i * n..][jj..j_end];
+
+                        if tiling.transpose_b {
+                            for (j, r) in (jj..j_end).zip(row_r.iter_mut()) {
+                                let col_b = &matrix_b[j * n..][kk..k_end];
+                                *r = row_a
+                                    .iter()
+                                    .zip(col_b)
+                                    .fold(*r, |acc, (&a, &b)| acc + a.into() * b.into());
+                            }
+                        } else {
+                            for (k, &a) in (kk..k_end).zip(row_a) {
+                                let a: R = a.into();
+                                let row_b = &matrix_b[k * n..][jj..j_end];
+                                for (r, &b) in row_r.iter_mut().zip(row_b) {
+                                    *r = *r + a * b.into();
+                                }
+                            }
+                        }
+                    }
+
+                    ops += ((i_end - ii) * (k_end - kk) * (j_end - jj)) as u64;
+                }
+            }
+        }
+
+        Ok(ops)
+    }
+
+    fn mul<T, R>(
+        matrix_a: &[&[T]],
+        matrix_b: &[&[T]],
+        matrix_r: &mut [&mut [R]],
+        timeout: Option<&Timeout>,
+        threadpool: Option<&rayon::ThreadPool>,
+    ) -> Result<u64, u64>
+    where
+        T: Into<R> + Copy + Send + Sync,
+        R: Add<Output = R> + Mul<Output = R> + Copy + Send + Sync,
+    {
+        let mut ops = 0;
+        timeout.reached_with_err(ops)?;
+
+        // `matrix_a` is m x k, `matrix_b` is k x p and `matrix_r` is m x p,
+        // odd dimensions are split unevenly and may produce empty blocks
+        let m = matrix_r.len();
+        let k = matrix_b.len();
+        let p = matrix_r.first().map_or(0, |row| row.len());
+        if m == 0 || k == 0 || p == 0 {
+            return Ok(ops);
+        }
+
+        if m == 1 && k == 1 && p == 1 {
+            matrix_r[0][0] = matrix_r[0][0] + matrix_a[0][0].into() * matrix_b[0][0].into();
+            ops += 1;
+            return Ok(ops);
+        }
+
+        let (a11, a12, a21, a22) = split!(matrix_a, m / 2, k / 2);
+        let (b11, b12, b21, b22) = split!(matrix_b, k / 2, p / 2);
+        let (mut r11, mut r12, mut r21, mut r22) = split!(mut matrix_r, m / 2, p / 2);
+
+        let tuples = [
+            (&mut r11, &a11, &b11, &a12, &b21),
+            (&mut r12, &a11, &b12, &a12, &b22),
+            (&mut r21, &a21, &b11, &a22, &b21),
+            (&mut r22, &a21, &b12, &a22, &b22),
+        ];
+
+        if let Some(_) = threadpool {
+            ops = tuples
+                .into_par_iter()
+                .map(|(r, a1, b1, a2, b2)| {
+                    if timeout.reached() {
+                        return Err(0);
+                    }
+
+                    let mut ops = mul(a1, b1, r, timeout, threadpool)?;
+                    ops = mul(a2, b2, r, timeout, threadpool).add(ops)?;
+
+                    Ok(ops)
+                })
+                .reduce(
+                    || Ok(0),
+                    |acc, next| match (acc, next) {
+                        (Ok(acc), Ok(next)) => Ok(acc + next),
+                        _ => Err(acc.value() + next.value()),
+                    },
+                )
+                .add(ops)?;
+        } else {
+            for (r, a1, b1, a2, b2) in tuples {
+                timeout.reached_with_err(ops)?;
+
+                ops = mul(a1, b1, r, timeout, threadpool).add(ops)?;
+                ops = mul(a2, b2, r, timeout, threadpool).add(ops)?;
+            }
+        }
+
+        Ok(ops)
+    }
+}
+
+pub struct Config {
+    pub rng: Box<dyn rand::RngCore>,
+
+    pub duration: Duration,
+
+    pub n: usize,
+    pub element_type: ElementType,
+    /// Additionally runs the cache-blocked kernel for another `duration`.
+    pub tiling: Option<Tiling>,
+    pub fft: Option<fft::Config>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            rng: Box::new(rand::thread_rng()),
+            duration: Duration::from_secs(10),
+            n: 4096,
+            element_type: ElementType::default(),
+            tiling: None,
+            fft: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Tiling {
+    pub tile_size: usize,
+    /// Multiplies by a transposed `matrix_b`, so that both operands are read row by row.
+    pub transpose_b: bool,
+}
+
+impl Default for Tiling {
+    fn default() -> Self {
+        Self {
+            tile_size: 64,
+            transpose_b: false,
+        }
+    }
+}
+
+impl fmt::Display for Tiling {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x{}", self.tile_size, self.tile_size)?;
+        if self.transpose_b {
+            write!(f, ", transposed B")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Type of the multiplied matrix elements, i8 is accumulated in `i32` and i16 in `i64`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ElementType {
+    I8,
+    I16,
+    #[default]
+    F32,
+    F64,
+}
+
+impl ElementType {
+    fn unit(&self) -> &'static str {
+        match self {
+            ElementType::I8 | ElementType::I16 => "GOPS",
+            ElementType::F32 | ElementType::F64 => "GFLOPS",
+        }
+    }
+}
+
+impl fmt::Display for ElementType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ElementType::I8 => write!(f, "i8 -> i32"),
+            ElementType::I16 => write!(f, "i16 -> i64"),
+            ElementType::F32 => write!(f, "f32"),
+            ElementType::F64 => write!(f, "f64"),
+        }
+    }
+}
+
+/// Instructions used to multiply `i8` matrices, other element types always use [Kernel::Scalar].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kernel {
+    Scalar,
+    Sve,
+    Neon,
+    NeonDotprod,
+    NeonI8mm,
+    Sse41,
+    Avx2,
+}
+
+impl Kernel {
+    fn select(features: &CpuFeatures, element_type: ElementType) -> Self {
+        if element_type != ElementType::I8 {
+            return Kernel::Scalar;
+        }
+
+        if cfg!(target_arch = "aarch64") && features.sve && features.i8mm {
+            return Kernel::Sve;
+        }
+
+        if cfg!(target_arch = "aarch64") && features.neon {
+            return if features.i8mm {
+                Kernel::NeonI8mm
+            } else if features.dotprod {
+                Kernel::NeonDotprod
+            } else {
+                Kernel::Neon
+            };
+        }
+
+        if cfg!(target_arch = "x86_64") {
+            if features.avx2 {
+                return Kernel::Avx2;
+            }
+            if features.sse41 {
+                return Kernel::Sse41;
+            }
+        }
+
+        Kernel::Scalar
+    }
+}
+
+impl fmt::Display for Kernel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Kernel::Scalar => write!(f, "scalar"),
+            Kernel::Sve => write!(f, "sve"),
+            Kernel::Neon => write!(f, "neon"),
+            Kernel::NeonDotprod => write!(f, "neon sdot"),
+            Kernel::NeonI8mm => write!(f, "neon smmla"),
+            Kernel::Sse41 => write!(f, "sse4.1"),
+            Kernel::Avx2 => write!(f, "avx2"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Empty,
+    Mismatch(usize, usize),
+    Fft(fft::Error),
+}
+
+pub struct Report {
+    pub element_type: ElementType,
+    pub kernel: Kernel,
+    /// Effective matrix size the benchmark ran with.
+    pub n: usize,
+    pub duration: Duration,
+    pub ops: u64,
+    pub tps: f64,
+    /// Number of completed matrix multiplications.
+    pub multiplications: u64,
+    /// Billions of operations per second, counting `2·n³` per completed multiplication.
+    pub gops: f64,
+    pub tiled: Option<TiledReport>,
+    pub fft: Option<fft::Report>,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "math ({}, n = {}, {}) ... {:.2} {}",
+            self.element_type,
+            self.n,
+            self.kernel,
+            self.gops,
+            self.element_type.unit()
+        )?;
+        if let Some(tiled) = &self.tiled {
+            write!(
+                f,
+                ", tiled ({}) ... {:.2} {}",
+                tiled.tiling,
+                tiled.gops,
+                self.element_type.unit()
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+pub struct TiledReport {
+    pub tiling: Tiling,
+    pub duration: Duration,
+    pub ops: u64,
+    pub tps: f64,
+    pub multiplications: u64,
+    pub gops: f64,
+}
+
+struct ReportBuilder {
+    element_type: ElementType,
+    kernel: Kernel,
+    n: usize,
+    duration: Duration,
+    ops: u64,
+    /// Time spent in completed multiplications.
+    completed_duration: Duration,
+    multiplications: u64,
+    tiled: Option<(Tiling, Box<ReportBuilder>)>,
+}
+
+impl ReportBuilder {
+    fn new(element_type: ElementType, kernel: Kernel, n: usize) -> Self {
+        Self {
+            element_type,
+            kernel,
+            n,
+            duration: Duration::ZERO,
+            ops: 0,
+            completed_duration: Duration::ZERO,
+            multiplications: 0,
+            tiled: None,
+        }
+    }
+
+    fn add(&mut self, duration: Duration, result: Result<u64, u64>) {
+        self.duration += duration;
+        self.ops += result.value();
+
+        if result.is_ok() {
+            self.completed_duration += duration;
+            self.multiplications += 1;
+        }
+    }
+
+    fn set_tiled(&mut self, tiling: Tiling, tiled: ReportBuilder) {
+        self.tiled = Some((tiling, Box::new(tiled)));
+    }
+
+    fn tps(&self) -> f64 {
+        match self.duration {
+            Duration::ZERO => 0.,
+            _ => self.ops as f64 / self.duration.as_secs_f64(),
+        }
+    }
+
+    fn gops(&self) -> f64 {
+        let n = self.n as f64;
+
+        match self.completed_duration {
+            Duration::ZERO => 0.,
+            _ => {
+                2. * n * n * n * self.multiplications as f64
+                    / self.completed_duration.as_secs_f64()
+                    / 1e9
+            }
+        }
+    }
+
+    fn build(self) -> Report {
+        Report {
+            element_type: self.element_type,
+            kernel: self.kernel,
+            n: self.n,
+            duration: self.duration,
+            ops: self.ops,
+            tps: self.tps(),
+            multiplications: self.multiplications,
+            gops: self.gops(),
+            tiled: self.tiled.map(|(tiling, tiled)| TiledReport {
+                tiling,
+                duration: tiled.duration,
+                ops: tiled.ops,
+                tps: tiled.tps(),
+                multiplications: tiled.multiplications,
+                gops: tiled.gops(),
+            }),
+            fft: None,
+        }
+    }
+}
+
+struct Context<T, R> {
+    rng: Box<dyn rand::RngCore>,
+
+    n: usize,
+    matrix_a: Vec<T>,
+    matrix_b: Vec<T>,
+    matrix_r: Vec<R>,
+
+    timeout: Timeout,
+}
+
+impl<T, R> Context<T, R>
+where
+    R: Copy + Default,
+    [T]: Fill,
+{
+    fn new(config: Config) -> Self {
+        let n = config.n.max(1);
+
+        let matrix_a = vec_with_len!(n * n);
+        let matrix_b = vec_with_len!(n * n);
+        let matrix_r = vec_with_len!(n * n);
+
+        let timeout = Timeout::new(config.duration);
+
+        Self {
+            rng: config.rng,
+            n,
+            matrix_a,
+            matrix_b,
+            matrix_r,
+            timeout,
+        }
+    }
+
+    fn reset_matrices(&mut self) {
+        self.rng.fill(&mut self.matrix_a[..]);
+        self.rng.fill(&mut self.matrix_b[..]);
+
+        let r_size = self.matrix_r.len();
+        self.matrix_r.clear();
+        self.matrix_r.resize(r_size, R::default());
+    }
+
+    fn transpose_b(&mut self) {
+        for i in 0..self.n {
+            for j in (i + 1)..self.n {
+                self.matrix_b.swap(i * self.n + j, j * self.n + i);
+            }
+        }
+    }
+
+    fn reset_timeout(&mut self) {
+        self.timeout = Timeout::new(self.timeout.duration);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::*;
+
+    #[test]
+    fn test_bench() {
+        let duration = Duration::from_millis(10000);
+        let start = Instant::now();
+        let result = bench(
+            &CpuFeatures {
+                num_cores: 1,
+                ..Default::default()
+            },
+            Config {
+                duration,
+                n: 10,
+                ..Default::default()
+            },
+        );
+        let elapsed = start.elapsed();
+
+        assert!(result.is_ok(), "expected success");
+        let result = result.unwrap();
+        assert_eq!(10, result.n);
+        assert!(result.ops > 0);
+        assert!(result.tps > 0.);
+        assert!(result.multiplications > 0);
+        assert!(result.gops > 0.);
+        assert!(elapsed >= duration && elapsed <= duration + Duration::from_millis(100));
+
+        println!("{result}");
+    }
+
+    #[test]
+    fn test_report_gops() {
+        let mut report_builder = ReportBuilder::new(ElementType::F32, Kernel::Scalar, 1000);
+        report_builder.add(Duration::from_secs(1), Ok(1_000_000_000));
+        report_builder.add(Duration::from_secs(1), Err(500_000_000));
+
+        let report = report_builder.build();
+        assert_eq!(1, report.multiplications);
+        assert_eq!(2., report.gops);
+        assert_eq!(750_000_000., report.tps);
+    }
+
+    #[test]
+    fn test_bench_multithread() {
+        let duration = Duration::from_millis(10000);
+        let start = Instant::now();
+        let result = bench_multithread(
+            &CpuFeatures {
+                num_cores: 8,
+                ..Default::default()
+            },
+            Config {
+                duration,
+                n: 10,
+                ..Default::default()
+            },
+        );
+        let elapsed = start.elapsed();
+
+        assert!(result.is_ok(), "expected success");
+        let result = result.unwrap();
+        assert_eq!(10, result.n);
+        assert!(result.ops > 0);
+        assert!(result.tps > 0.);
+        assert!(elapsed >= duration && elapsed <= duration + Duration::from_millis(100));
+
+        println!("{result}");
+    }
+
+    #[test]
+    fn test_bench_element_types() {
+        let features = CpuFeatures {
+            num_cores: 2,
+            ..Default::default()
+        };
+        let duration = Duration::from_millis(200);
+
+        for element_type in [
+            ElementType::I8,
+            ElementType::I16,
+            ElementType::F32,
+            ElementType::F64,
+        ] {
+            let config = || Config {
+                duration,
+                n: 16,
+                element_type,
+                ..Default::default()
+            };
+
+            let result = bench(&features, config());
+            assert!(result.is_ok(), "expected success");
+            let result = result.unwrap();
+            assert_eq!(element_type, result.element_type);
+            assert!(result.ops > 0);
+
+            let result = bench_multithread(&features, config());
+            assert!(result.is_ok(), "expected success");
+            let result = result.unwrap();
+            assert_eq!(element_type, result.element_type);
+            assert!(result.ops > 0);
+
+            println!("{result}");
+        }
+    }
+
+    #[test]
+    fn test_bench_default_element_type() {
+        let features = CpuFeatures {
+            num_cores: 2,
+            ..Default::default()
+        };
+        let config = || Config {
+            duration: Duration::from_millis(100),
+            n: 16,
+            ..Default::default()
+        };
+
+        let result = bench(&features, config());
+        assert!(result.is_ok(), "expected success");
+        assert_eq!(ElementType::F32, result.unwrap().element_type);
+
+        let result = bench_multithread(&features, config());
+        assert!(result.is_ok(), "expected success");
+        assert_eq!(ElementType::F32, result.unwrap().element_type);
+    }
+
+    #[test]
+    fn test_bench_simd() {
+        #[allow(unused_mut)]
+        let mut features = CpuFeatures {
+            num_cores: 2,
+            neon: cfg!(target_arch = "aarch64"),
+            ..Default::default()
+        };
+        #[cfg(target_arch = "x86_64")]
+        {
+            features.sse41 = std::arch::is_x86_feature_detected!("sse4.1");
+            features.avx2 = std::arch::is_x86_feature_detected!("avx2");
+        }
+
+        let config = || Config {
+            duration: Duration::from_millis(500),
+            n: 33,
+            element_type: ElementType::I8,
+            ..Default::default()
+        };
+
+        for result in [
+            bench(&features, config()),
+            bench_multithread(&features, config()),
+        ] {
+            assert!(result.is_ok(), "expected success");
+            let result = result.unwrap();
+            assert_eq!(Kernel::select(&features, ElementType::I8), result.kernel);
+            assert!(result.ops > 0);
+
+            println!("{result}");
+        }
+    }
+
+    #[test]
+    fn test_par_row_pairs() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let threadpool = rayon::ThreadPoolBuilder::new()
+            .num_threads(4)
+            .build()
+            .unwrap();
+
+        for n in [8, 9] {
+            let mut context = Context::<i8, i32>::new(Config {
+                n,
+                ..Default::default()
+            });
+            context.reset_matrices();
+
+            let blocks = AtomicUsize::new(0);
+            let result = par_row_pairs(
+                &threadpool,
+                &context.matrix_a[..],
+                &mut context.matrix_r[..],
+                n,
+                |rows_a, rows_r| {
+                    simd::mul_2x2(
+                        rows_a,
+                        &context.matrix_b[..],
+                        rows_r,
+                        n,
+                        None,
+                        simd::dot,
+                        |a, b| {
+                            blocks.fetch_add(1, Ordering::Relaxed);
+                            [
+                                simd::dot(a[0], b[0]),
+                                simd::dot(a[0], b[1]),
+                                simd::dot(a[1], b[0]),
+                                simd::dot(a[1], b[1]),
+                            ]
+                        },
+                    )
+                },
+            );
+
+            assert_eq!(Ok((n * n * n) as u64), result);
+            assert_eq!((n / 2) * (n / 2), blocks.into_inner());
+            for i in 0..n {
+                for j in 0..n {
+                    assert_eq!(
+                        simd::dot(
+                            &context.matrix_a[i * n..][..n],
+                            &context.matrix_b[j * n..][..n]
+                        ),
+                        context.matrix_r[i * n + j]
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_verify_simd() {
+        let n = 8;
+        let mut context = Context::<i8, i32>::new(Config {
+            n,
+            ..Default::default()
+        });
+        context.reset_matrices();
+        context.transpose_b();
+
+        context.matrix_r.fill(1);
+        assert!(matches!(
+            verify_simd(&mut context),
+            Err(Error::Mismatch(_, _))
+        ));
+
+        simd::run_test(
+            Kernel::Scalar,
+            &context.matrix_a,
+            &context.matrix_b,
+            &mut context.matrix_r,
+            n,
+            None,
+        )
+        .unwrap();
+        assert!(verify_simd(&mut context).is_ok());
+    }
+
+    #[test]
+    fn test_bench_fft() {
+        let features = CpuFeatures {
+            num_cores: 2,
+            ..Default::default()
+        };
+        let config = || Config {
+            duration: Duration::from_millis(200),
+            n: 16,
+            fft: Some(fft::Config {
+                duration: Duration::from_millis(200),
+                n: 64,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        for result in [
+            bench(&features, config()),
+            bench_multithread(&features, config()),
+        ] {
+            assert!(result.is_ok(), "expected success");
+            let result = result.unwrap();
+            let fft = result.fft.expect("expected fft report");
+            assert_eq!(64, fft.n);
+            assert!(fft.transforms > 0);
+        }
+    }
+
+    #[test]
+    fn test_kernel_select() {
+        let features = CpuFeatures {
+            neon: true,
+            dotprod: true,
+            sse41: true,
+            avx2: true,
+            ..Default::default()
+        };
+
+        let expected = if cfg!(target_arch = "aarch64") {
+            Kernel::NeonDotprod
+        } else if cfg!(target_arch = "x86_64") {
+            Kernel::Avx2
+        } else {
+            Kernel::Scalar
+        };
+        assert_eq!(expected, Kernel::select(&features, ElementType::I8));
+        assert_eq!(Kernel::Scalar, Kernel::select(&features, ElementType::F32));
+
+        let features = CpuFeatures {
+            sve: true,
+            i8mm: true,
+            ..features
+        };
+        let expected = if cfg!(target_arch = "aarch64") {
+            Kernel::Sve
+        } else {
+            expected
+        };
+        assert_eq!(expected, Kernel::select(&features, ElementType::I8));
+    }
+
+    #[test]
+    fn test_matrix() {
+        let matrix_a = [
+            &[80, 43, 16, 5],
+            &[70, 41, 38, 62],
+            &[31, 19, 97, 39],
+            &[66, 6, 40, 28],
+        ]
+        .map(|row| &row[..]);
+
+        let matrix_b = [
+            &[24, 12, 24, 29],
+            &[83, 59, 32, 44],
+            &[97, 38, 67, 13],
+            &[98, 64, 68, 29],
+        ]
+        .map(|row| &row[..]);
+
+        let matrix_r_expected = [
+            [7531, 4425, 4708, 4565],
+            [14845, 8671, 9754, 6126],
+            [15552, 7675, 10503, 4127],
+            [8706, 4458, 6360, 3510],
+        ];
+
+        let mut matrix_r = [[0i32; 4]; 4];
+        let mut matrix_r_slice: Vec<&mut [i32]> =
+            matrix_r.iter_mut().map(|row| &mut row[..]).collect();
+
+        let result = matrix::run_test(&matrix_a, &matrix_b, &mut matrix_r_slice, None);
+
+        assert!(result.is_ok(), "expected success");
+        assert_eq!(64, result.unwrap());
+        assert_eq!(matrix_r_expected, matrix_r);
+    }
+
+    #[test]
+    fn test_matrix_multithread() {
+        let matrix_a = [
+            &[80, 43, 16, 5],
+            &[70, 41, 38, 62],
+            &[31, 19, 97, 39],
+            &[66, 6, 40, 28],
+        ]
+        .map(|row| &row[..]);
+
+        let matrix_b = [
+            &[24, 12, 24, 29],
+            &[83, 59, 32, 44],
+            &[97, 38, 67, 13],
+            &[98, 64, 68, 29],
+        ]
+        .map(|row| &row[..]);
+
+        let matrix_r_expected = [
+            [7531, 4425, 4708, 4565],
+            [14845, 8671, 9754, 6126],
+            [15552, 7675, 10503, 4127],
+            [8706, 4458, 6360, 3510],
+        ];
+
+        let mut matrix_r = [[0i32; 4]; 4];
+        let mut matrix_r_slice: Vec<&mut [i32]> =
+            matrix_r.iter_mut().map(|row| &mut row[..]).collect();
+
+        let threadpool = rayon::ThreadPoolBuilder::new()
+            .num_threads(2)
+            .build()
+            .unwrap();
+        let result = matrix::run_test_multithread(
+            &threadpool,
+            &matrix_a,
+            &matrix_b,
+            &mut matrix_r_slice,
+            None,
+        );
+
+        assert!(result.is_ok(), "expected success");
+        assert_eq!(64, result.unwrap());
+        assert_eq!(matrix_r_expected, matrix_r);
+    }
+
+    #[test]
+    fn test_bench_tiled() {
+        let features = CpuFeatures {
+            num_cores: 2,
+            ..Default::default()
+        };
+        let duration = Duration::from_millis(200);
+
+        for transpose_b in [false, true] {
+            let config = || Config {
+                duration,
+                n: 32,
+                tiling: Some(Tiling {
+                    tile_size: 8,
+                    transpose_b,
+                }),
+                ..Default::default()
+            };
+
+            for result in [
+                bench(&features, config()),
+                bench_multithread(&features, config()),
+            ] {
+                assert!(result.is_ok(), "expected success");
+                let result = result.unwrap();
+                let tiled = result.tiled.as_ref().expect("expected tiled report");
+                assert!(tiled.ops > 0);
+                assert!(tiled.tps > 0.);
+
+                println!("{result}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_matrix_uneven() {
+        let n = 7;
+        let mut rng = rand::thread_rng();
+        let matrix_a: Vec<i8> = (0..n * n).map(|_| rng.gen()).collect();
+        let matrix_b: Vec<i8> = (0..n * n).map(|_| rng.gen()).collect();
+
+        let mut matrix_r_expected = vec![0i32; n * n];
+        for i in 0..n {
+            for j in 0..n {
+                matrix_r_expected[i * n + j] = (0..n)
+                    .map(|k| matrix_a[i * n + k] as i32 * matrix_b[k * n + j] as i32)
+                    .sum();
+            }
+        }
+
+        let matrix_a = matrix_a.chunks(n).collect::<Vec<_>>();
+        let matrix_b = matrix_b.chunks(n).collect::<Vec<_>>();
+
+        let mut matrix_r = vec![0i32; n * n];
+        let result = matrix::run_test(
+            &matrix_a,
+            &matrix_b,
+            &mut matrix_r.chunks_mut(n).collect::<Vec<_>>(),
+            None,
+        );
+        assert_eq!(Ok((n * n * n) as u64), result);
+        assert_eq!(matrix_r_expected, matrix_r);
+
+        let threadpool = rayon::ThreadPoolBuilder::new()
+            .num_threads(2)
+            .build()
+            .unwrap();
+        let mut matrix_r = vec![0i32; n * n];
+        let result = matrix::run_test_multithread(
+            &threadpool,
+            &matrix_a,
+            &matrix_b,
+            &mut matrix_r.chunks_mut(n).collect::<Vec<_>>(),
+            None,
+        );
+        assert_eq!(Ok((n * n * n) as u64), result);
+        assert_eq!(matrix_r_expected, matrix_r);
+    }
+
+    #[test]
+    fn test_matrix_tiled() {
+        let n = 16;
+        let mut rng = rand::thread_rng();
+        let matrix_a: Vec<i8> = (0..n * n).map(|_| rng.gen()).collect();
+        let matrix_b: Vec<i8> = (0..n * n).map(|_| rng.gen()).collect();
+        let matrix_b_t: Vec<i8> = (0..n * n).map(|i| matrix_b[(i % n) * n + i / n]).collect();
+
+        let mut matrix_r_expected = vec![0i32; n * n];
+        matrix::run_test(
+            &matrix_a.chunks(n).collect::<Vec<_>>(),
+            &matrix_b.chunks(n).collect::<Vec<_>>(),
+            &mut matrix_r_expected.chunks_mut(n).collect::<Vec<_>>(),
+            None,
+        )
+        .unwrap();
+
+        let threadpool = rayon::ThreadPoolBuilder::new()
+            .num_threads(2)
+            .build()
+            .unwrap();
+
+        for transpose_b in [false, true] {
+            let tiling = Tiling {
+                tile_size: 5,
+                transpose_b,
+            };
+            let matrix_b = if transpose_b { &matrix_b_t } else { &matrix_b };
+
+            let mut matrix_r = vec![0i32; n * n];
+            let result =
+                matrix::run_test_tiled(&matrix_a, matrix_b, &mut matrix_r, n, &tiling, None);
+            assert_eq!(Ok((n * n * n) as u64), result);
+            assert_eq!(matrix_r_expected, matrix_r);
+
+            let mut matrix_r = vec![0i32; n * n];
+            let result = matrix::run_test_tiled_multithread(
+                &threadpool,
+                &matrix_a,
+                matrix_b,
+                &mut matrix_r,
+                n,
+                &tiling,
+                None,
+            );
+            assert_eq!(Ok((n * n * n) as u64), result);
+            assert_eq!(matrix_r_expected, matrix_r);
+        }
+    }
+}
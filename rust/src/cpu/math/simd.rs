@@ -0,0 +1,371 @@
+use crate::utils::{Expirable, Timeout};
+
+use super::Kernel;
+
+/// Multiplies the rows of `matrix_a` by `matrix_b` into the matching rows of `matrix_r`
+/// using the instructions selected by `kernel`. `matrix_b` is expected to be transposed.
+pub(super) fn run_test(
+    kernel: Kernel,
+    matrix_a: &[i8],
+    matrix_b: &[i8],
+    matrix_r: &mut [i32],
+    n: usize,
+    timeout: Option<&Timeout>,
+) -> Result<u64, u64> {
+    match kernel {
+        #[cfg(target_arch = "aarch64")]
+        Kernel::Sve => mul(matrix_a, matrix_b, matrix_r, n, timeout, |a, b| unsafe {
+            aarch64::dot_sve(a, b)
+        }),
+        #[cfg(target_arch = "aarch64")]
+        Kernel::Neon => mul(matrix_a, matrix_b, matrix_r, n, timeout, |a, b| unsafe {
+            aarch64::dot_neon(a, b)
+        }),
+        #[cfg(target_arch = "aarch64")]
+        Kernel::NeonDotprod => mul(matrix_a, matrix_b, matrix_r, n, timeout, |a, b| unsafe {
+            aarch64::dot_neon_sdot(a, b)
+        }),
+        #[cfg(target_arch = "aarch64")]
+        Kernel::NeonI8mm => mul_2x2(
+            matrix_a,
+            matrix_b,
+            matrix_r,
+            n,
+            timeout,
+            |a, b| unsafe { aarch64::dot_neon(a, b) },
+            |a, b| unsafe { aarch64::dot_2x2_neon_smmla(a, b) },
+        ),
+        #[cfg(target_arch = "x86_64")]
+        Kernel::Sse41 => mul(matrix_a, matrix_b, matrix_r, n, timeout, |a, b| unsafe {
+            x86_64::dot_sse41(a, b)
+        }),
+        #[cfg(target_arch = "x86_64")]
+        Kernel::Avx2 => mul(matrix_a, matrix_b, matrix_r, n, timeout, |a, b| unsafe {
+            x86_64::dot_avx2(a, b)
+        }),
+        _ => mul(matrix_a, matrix_b, matrix_r, n, timeout, dot),
+    }
+}
+
+/// Scalar dot product, the reference for all kernels.
+pub(super) fn dot(a: &[i8], b: &[i8]) -> i32 {
+    a.iter().zip(b).map(|(&a, &b)| a as i32 * b as i32).sum()
+}
+
+fn mul(
+    matrix_a: &[i8],
+    matrix_b: &[i8],
+    matrix_r: &mut [i32],
+    n: usize,
+    timeout: Option<&Timeout>,
+    dot: impl Fn(&[i8], &[i8]) -> i32,
+) -> Result<u64, u64> {
+    let mut ops = 0;
+    for (row_a, row_r) in matrix_a.chunks(n).zip(matrix_r.chunks_mut(n)) {
+        timeout.reached_with_err(ops)?;
+
+        for (r, col_b) in row_r.iter_mut().zip(matrix_b.chunks(n)) {
+            *r = dot(row_a, col_b);
+        }
+        ops += (n * n) as u64;
+    }
+
+    Ok(ops)
+}
+
+/// Computes the result in 2x2 blocks, the remaining odd row and column fall back to `dot`.
+#[cfg_attr(not(target_arch = "aarch64"), allow(dead_code))]
+pub(super) fn mul_2x2(
+    matrix_a: &[i8],
+    matrix_b: &[i8],
+    matrix_r: &mut [i32],
+    n: usize,
+    timeout: Option<&Timeout>,
+    dot: impl Fn(&[i8], &[i8]) -> i32,
+    dot_2x2: impl Fn([&[i8]; 2], [&[i8]; 2]) -> [i32; 4],
+) -> Result<u64, u64> {
+    let mut ops = 0;
+    for (rows_a, rows_r) in matrix_a.chunks(2 * n).zip(matrix_r.chunks_mut(2 * n)) {
+        timeout.reached_with_err(ops)?;
+
+        if rows_a.len() < 2 * n {
+            return mul(rows_a, matrix_b, rows_r, n, None, &dot).map(|row_ops| ops + row_ops);
+        }
+
+        let (row_a0, row_a1) = rows_a.split_at(n);
+        let (row_r0, row_r1) = rows_r.split_at_mut(n);
+        for (j, cols_b) in matrix_b.chunks(2 * n).enumerate() {
+            if cols_b.len() < 2 * n {
+                row_r0[2 * j] = dot(row_a0, cols_b);
+                row_r1[2 * j] = dot(row_a1, cols_b);
+                continue;
+            }
+
+            let (col_b0, col_b1) = cols_b.split_at(n);
+            let [r00, r01, r10, r11] = dot_2x2([row_a0, row_a1], [col_b0, col_b1]);
+            row_r0[2 * j] = r00;
+            row_r0[2 * j + 1] = r01;
+            row_r1[2 * j] = r10;
+            row_r1[2 * j + 1] = r11;
+        }
+        ops += (2 * n * n) as u64;
+    }
+
+    Ok(ops)
+}
+
+#[cfg(target_arch = "aarch64")]
+mod aarch64 {
+    use std::arch::{aarch64::*, asm};
+
+    /// SVE intrinsics are not available in `core::arch` yet, the loop is written in assembly.
+    #[target_feature(enable = "sve")]
+    pub(super) unsafe fn dot_sve(a: &[i8], b: &[i8]) -> i32 {
+        let len = usize::min(a.len(), b.len());
+
+        let sum: i64;
+        asm!(
+            "mov {k}, #0",
+            "dup z0.s, #0",
+            "whilelo p0.b, {k}, {len}",
+            "b.none 3f",
+            "2:",
+            "ld1b {{ z1.b }}, p0/z, [{a}, {k}]",
+            "ld1b {{ z2.b }}, p0/z, [{b}, {k}]",
+            "sdot z0.s, z1.b, z2.b",
+            "incb {k}",
+            "whilelo p0.b, {k}, {len}",
+            "b.first 2b",
+            "3:",
+            "ptrue p0.s",
+            "saddv {sum:d}, p0, z0.s",
+            a = in(reg) a.as_ptr(),
+            b = in(reg) b.as_ptr(),
+            len = in(reg) len,
+            k = out(reg) _,
+            sum = out(vreg) sum,
+            out("v0") _,
+            out("v1") _,
+            out("v2") _,
+            out("p0") _,
+            options(pure, readonly, nostack),
+        );
+
+        sum as i32
+    }
+
+    #[target_feature(enable = "neon")]
+    pub(super) unsafe fn dot_neon(a: &[i8], b: &[i8]) -> i32 {
+        let len = usize::min(a.len(), b.len());
+        let tail = len - len % 16;
+
+        let mut acc = vdupq_n_s32(0);
+        for k in (0..tail).step_by(16) {
+            let a = vld1q_s8(a.as_ptr().add(k));
+            let b = vld1q_s8(b.as_ptr().add(k));
+
+            acc = vpadalq_s16(acc, vmull_s8(vget_low_s8(a), vget_low_s8(b)));
+            acc = vpadalq_s16(acc, vmull_high_s8(a, b));
+        }
+
+        vaddvq_s32(acc) + super::dot(&a[tail..len], &b[tail..len])
+    }
+
+    #[target_feature(enable = "neon,dotprod")]
+    pub(super) unsafe fn dot_neon_sdot(a: &[i8], b: &[i8]) -> i32 {
+        let len = usize::min(a.len(), b.len());
+        let tail = len - len % 16;
+
+        let mut acc = vdupq_n_s32(0);
+        for k in (0..tail).step_by(16) {
+            let a = vld1q_s8(a.as_ptr().add(k));
+            let b = vld1q_s8(b.as_ptr().add(k));
+
+            asm!(
+                "sdot {acc:v}.4s, {a:v}.16b, {b:v}.16b",
+                acc = inout(vreg) acc,
+                a = in(vreg) a,
+                b = in(vreg) b,
+                options(pure, nomem, nostack),
+            );
+        }
+
+        vaddvq_s32(acc) + super::dot(&a[tail..len], &b[tail..len])
+    }
+
+    /// Returns `[a0·b0, a0·b1, a1·b0, a1·b1]`.
+    #[target_feature(enable = "neon,i8mm")]
+    pub(super) unsafe fn dot_2x2_neon_smmla(a: [&[i8]; 2], b: [&[i8]; 2]) -> [i32; 4] {
+        let len = a.iter().chain(&b).map(|row| row.len()).min().unwrap_or(0);
+        let tail = len - len % 8;
+
+        let mut acc = vdupq_n_s32(0);
+        for k in (0..tail).step_by(8) {
+            let a = vcombine_s8(vld1_s8(a[0].as_ptr().add(k)), vld1_s8(a[1].as_ptr().add(k)));
+            let b = vcombine_s8(vld1_s8(b[0].as_ptr().add(k)), vld1_s8(b[1].as_ptr().add(k)));
+
+            asm!(
+                "smmla {acc:v}.4s, {a:v}.16b, {b:v}.16b",
+                acc = inout(vreg) acc,
+                a = in(vreg) a,
+                b = in(vreg) b,
+                options(pure, nomem, nostack),
+            );
+        }
+
+        let mut r = [0i32; 4];
+        vst1q_s32(r.as_mut_ptr(), acc);
+        for (r, (i, j)) in r.iter_mut().zip([(0, 0), (0, 1), (1, 0), (1, 1)]) {
+            *r += super::dot(&a[i][tail..len], &b[j][tail..len]);
+        }
+
+        r
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86_64 {
+    use std::arch::x86_64::*;
+
+    #[target_feature(enable = "sse4.1")]
+    pub(super) unsafe fn dot_sse41(a: &[i8], b: &[i8]) -> i32 {
+        let len = usize::min(a.len(), b.len());
+        let tail = len - len % 8;
+
+        let mut acc = _mm_setzero_si128();
+        for k in (0..tail).step_by(8) {
+            let a = _mm_cvtepi8_epi16(_mm_loadl_epi64(a.as_ptr().add(k) as *const __m128i));
+            let b = _mm_cvtepi8_epi16(_mm_loadl_epi64(b.as_ptr().add(k) as *const __m128i));
+
+            acc = _mm_add_epi32(acc, _mm_madd_epi16(a, b));
+        }
+
+        hsum(acc) + super::dot(&a[tail..len], &b[tail..len])
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn dot_avx2(a: &[i8], b: &[i8]) -> i32 {
+        let len = usize::min(a.len(), b.len());
+        let tail = len - len % 16;
+
+        let mut acc = _mm256_setzero_si256();
+        for k in (0..tail).step_by(16) {
+            let a = _mm256_cvtepi8_epi16(_mm_loadu_si128(a.as_ptr().add(k) as *const __m128i));
+            let b = _mm256_cvtepi8_epi16(_mm_loadu_si128(b.as_ptr().add(k) as *const __m128i));
+
+            acc = _mm256_add_epi32(acc, _mm256_madd_epi16(a, b));
+        }
+
+        let acc = _mm_add_epi32(
+            _mm256_castsi256_si128(acc),
+            _mm256_extracti128_si256::<1>(acc),
+        );
+
+        hsum(acc) + super::dot(&a[tail..len], &b[tail..len])
+    }
+
+    #[target_feature(enable = "sse2")]
+    unsafe fn hsum(v: __m128i) -> i32 {
+        let v = _mm_add_epi32(v, _mm_shuffle_epi32::<0b01_00_11_10>(v));
+        let v = _mm_add_epi32(v, _mm_shuffle_epi32::<0b10_11_00_01>(v));
+
+        _mm_cvtsi128_si32(v)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+
+    fn available_kernels() -> Vec<Kernel> {
+        #[allow(unused_mut)]
+        let mut kernels = vec![Kernel::Scalar];
+
+        #[cfg(target_arch = "aarch64")]
+        {
+            use std::arch::is_aarch64_feature_detected;
+
+            if is_aarch64_feature_detected!("sve") {
+                kernels.push(Kernel::Sve);
+            }
+            kernels.push(Kernel::Neon);
+            if is_aarch64_feature_detected!("dotprod") {
+                kernels.push(Kernel::NeonDotprod);
+            }
+            if is_aarch64_feature_detected!("i8mm") {
+                kernels.push(Kernel::NeonI8mm);
+            }
+        }
+
+        #[cfg(target_arch = "x86_64")]
+        {
+            use std::arch::is_x86_feature_detected;
+
+            if is_x86_feature_detected!("sse4.1") {
+                kernels.push(Kernel::Sse41);
+            }
+            if is_x86_feature_detected!("avx2") {
+                kernels.push(Kernel::Avx2);
+            }
+        }
+
+        kernels
+    }
+
+    #[test]
+    fn test_scalar() {
+        let n = 4;
+
+        let matrix_a = [
+            80, 43, 16, 5, //
+            70, 41, 38, 62, //
+            31, 19, 97, 39, //
+            66, 6, 40, 28,
+        ];
+
+        let matrix_b = [
+            24, 83, 97, 98, //
+            12, 59, 38, 64, //
+            24, 32, 67, 68, //
+            29, 44, 13, 29,
+        ]; /* transposed */
+
+        let matrix_r_expected = [
+            7531, 4425, 4708, 4565, //
+            14845, 8671, 9754, 6126, //
+            15552, 7675, 10503, 4127, //
+            8706, 4458, 6360, 3510,
+        ];
+
+        let mut matrix_r = [0i32; 16];
+        let result = run_test(Kernel::Scalar, &matrix_a, &matrix_b, &mut matrix_r, n, None);
+
+        assert_eq!(Ok(64), result);
+        assert_eq!(matrix_r_expected, matrix_r);
+    }
+
+    #[test]
+    fn test_kernels() {
+        let mut rng = rand::thread_rng();
+
+        // odd sizes to cover the scalar tails
+        for n in [1, 7, 37] {
+            let matrix_a: Vec<i8> = (0..n * n).map(|_| rng.gen()).collect();
+            let matrix_b: Vec<i8> = (0..n * n).map(|_| rng.gen()).collect();
+
+            let matrix_r_expected: Vec<i32> = (0..n * n)
+                .map(|i| dot(&matrix_a[i / n * n..][..n], &matrix_b[i % n * n..][..n]))
+                .collect();
+
+            for kernel in available_kernels() {
+                let mut matrix_r = vec![0i32; n * n];
+                let result = run_test(kernel, &matrix_a, &matrix_b, &mut matrix_r, n, None);
+
+                assert_eq!(Ok((n * n * n) as u64), result, "{kernel}");
+                assert_eq!(matrix_r_expected, matrix_r, "{kernel}");
+            }
+        }
+    }
+}
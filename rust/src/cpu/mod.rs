@@ -1,29 +1,54 @@
 use std::fmt;
 
+use crate::CpuFeatures;
+
+pub(crate) mod bigint;
 pub(crate) mod crypto;
+pub(crate) mod inference;
 pub(crate) mod math;
 pub(crate) mod sort;
+pub(crate) mod zk;
 
 #[derive(Default)]
 pub struct Config {
     pub crypto: crypto::Config,
     pub math: math::Config,
     pub sort: sort::Config,
+    pub zk: Option<zk::Config>,
+    pub bigint: Option<bigint::Config>,
+    pub inference: Option<inference::Config>,
 }
 
 pub struct Report {
+    pub features: CpuFeatures,
     pub crypto: crypto::Report,
     pub math: math::Report,
     pub sort: sort::Report,
+    pub zk: Option<zk::Report>,
+    pub bigint: Option<bigint::Report>,
+    pub inference: Option<inference::Report>,
 }
 
 impl fmt::Display for Report {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let i = "::::";
         writeln!(f, "CPU")?;
+        writeln!(f, "{i} {}", self.features)?;
         writeln!(f, "{i} {}", self.crypto)?;
         writeln!(f, "{i} {}", self.math)?;
+        if let Some(fft) = &self.math.fft {
+            writeln!(f, "{i} {fft}")?;
+        }
         write!(f, "{i} {}", self.sort)?;
+        if let Some(zk) = &self.zk {
+            write!(f, "\n{i} {zk}")?;
+        }
+        if let Some(bigint) = &self.bigint {
+            write!(f, "\n{i} {bigint}")?;
+        }
+        if let Some(inference) = &self.inference {
+            write!(f, "\n{i} {inference}")?;
+        }
 
         Ok(())
     }
@@ -34,4 +59,7 @@ pub enum Error {
     Crypto(crypto::Error),
     Math(math::Error),
     Sort(sort::Error),
+    Zk(zk::Error),
+    Bigint(bigint::Error),
+    Inference(inference::Error),
 }
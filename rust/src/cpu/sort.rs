@@ -4,7 +4,7 @@ use std::{
     time::{Duration, Instant},
 };
 
-use rand::distributions::DistString;
+use rand::{distributions::DistString, Rng};
 
 use crate::{
     utils::{Expirable, GetValue, Timeout},
@@ -12,28 +12,51 @@ use crate::{
 };
 
 pub(crate) fn bench(_features: &CpuFeatures, config: Config) -> Result<Report, Error> {
-    let mut context = Context::new(config);
-    let mut report_builder = ReportBuilder::new();
+    match config.data_type {
+        DataType::String => bench_typed::<String>(config, None),
+        DataType::U32 => bench_typed::<u32>(config, None),
+        DataType::U64 => bench_typed::<u64>(config, None),
+        DataType::F64 => bench_typed::<f64>(config, None),
+        DataType::Record => bench_typed::<Record>(config, None),
+    }
+}
+
+pub(crate) fn bench_multithread(features: &CpuFeatures, config: Config) -> Result<Report, Error> {
+    let threadpool = rayon::ThreadPoolBuilder::new()
+        .num_threads(features.num_cores)
+        .build()
+        .unwrap();
+
+    match config.data_type {
+        DataType::String => bench_typed::<String>(config, Some(&threadpool)),
+        DataType::U32 => bench_typed::<u32>(config, Some(&threadpool)),
+        DataType::U64 => bench_typed::<u64>(config, Some(&threadpool)),
+        DataType::F64 => bench_typed::<f64>(config, Some(&threadpool)),
+        DataType::Record => bench_typed::<Record>(config, Some(&threadpool)),
+    }
+}
+
+fn bench_typed<T>(config: Config, threadpool: Option<&rayon::ThreadPool>) -> Result<Report, Error>
+where
+    T: Item,
+{
+    let mut context = Context::<T>::new(config);
+    let mut report_builder = ReportBuilder::new(context.data_type, context.algorithm);
 
     let mut start: Instant;
     'main: while !context.timeout.reached() {
-        for i in 0..context.data.len() {
-            if context.timeout.reached() {
-                break 'main;
-            }
-
-            context.data[i] =
-                rand::distributions::Alphanumeric.sample_string(&mut context.rng, context.item_len);
-        }
+        context.reset_data();
 
         start = Instant::now();
-        let ops = black_box(merge::run_test(
+        let ops = black_box(sort(
+            context.algorithm,
+            threadpool,
             &mut context.data[..],
             &mut context.temp[..],
             Some(&context.timeout),
         ));
 
-        report_builder.add(start.elapsed(), ops);
+        report_builder.add(start.elapsed(), ops, context.data.len());
 
         if ops.is_ok() {
             for i in 0..context.data.len() - 1 {
@@ -42,58 +65,181 @@ pub(crate) fn bench(_features: &CpuFeatures, config: Config) -> Result<Report, E
                 }
 
                 if context.data[i] > context.data[i + 1] {
-                    return Err(Error::Unsorted(context.data));
+                    return Err(Error::Unsorted(
+                        context.data.iter().map(ToString::to_string).collect(),
+                    ));
                 }
             }
         }
     }
 
-    Ok(report_builder.build())
+    Ok(report_builder.build(context.setup, context.timeout.start.elapsed()))
 }
 
-pub(crate) fn bench_multithread(features: &CpuFeatures, config: Config) -> Result<Report, Error> {
-    let mut context = Context::new(config);
-    let threadpool = rayon::ThreadPoolBuilder::new()
-        .num_threads(features.num_cores)
-        .build()
-        .unwrap();
-    let mut result_builder = ReportBuilder::new();
+fn sort<T: Item>(
+    algorithm: Algorithm,
+    threadpool: Option<&rayon::ThreadPool>,
+    data: &mut [T],
+    temp: &mut [T],
+    timeout: Option<&Timeout>,
+) -> Result<u64, u64> {
+    match (algorithm, threadpool) {
+        (Algorithm::Merge, None) => merge::run_test(data, temp, timeout),
+        (Algorithm::Merge, Some(threadpool)) => {
+            merge::run_test_multithread(threadpool, data, temp, timeout)
+        }
+        (Algorithm::Radix, None) => radix::run_test(data, temp, timeout),
+        (Algorithm::Radix, Some(threadpool)) => {
+            radix::run_test_multithread(threadpool, data, temp, timeout)
+        }
+        (Algorithm::Quick, None) => quick::run_test(data, timeout),
+        (Algorithm::Quick, Some(threadpool)) => {
+            quick::run_test_multithread(threadpool, data, timeout)
+        }
+        (Algorithm::Sample, None) => sample::run_test(data, temp, timeout),
+        (Algorithm::Sample, Some(threadpool)) => {
+            sample::run_test_multithread(threadpool, data, temp, timeout)
+        }
+    }
+}
 
-    let mut start: Instant;
-    'main: while !context.timeout.reached() {
-        for i in 0..context.data.len() {
-            if context.timeout.reached() {
-                break 'main;
-            }
+/// Splits `data` into consecutive buckets of the given lengths.
+fn split_buckets<'a, T>(mut data: &'a mut [T], lens: &[usize]) -> Vec<&'a mut [T]> {
+    let mut buckets = Vec::with_capacity(lens.len());
+    for &len in lens {
+        let (bucket, rest) = data.split_at_mut(len);
+        buckets.push(bucket);
+        data = rest;
+    }
 
-            context.data[i] =
-                rand::distributions::Alphanumeric.sample_string(&mut context.rng, context.item_len);
-        }
+    buckets
+}
 
-        start = Instant::now();
-        let ops = black_box(merge::run_test_multithread(
-            &threadpool,
-            &mut context.data[..],
-            &mut context.temp[..],
-            Some(&context.timeout),
-        ));
+/// Sums the results of the buckets sorted in parallel.
+fn reduce_ops(acc: Result<u64, u64>, next: Result<u64, u64>) -> Result<u64, u64> {
+    match (acc, next) {
+        (Ok(acc), Ok(next)) => Ok(acc + next),
+        _ => Err(acc.value() + next.value()),
+    }
+}
 
-        result_builder.add(start.elapsed(), ops);
+/// Sorted element, generated randomly before each run.
+trait Item: Clone + Default + PartialOrd + Send + Sync + fmt::Display {
+    fn random<R: Rng + ?Sized>(rng: &mut R, item_len: usize) -> Self;
 
-        if ops.is_ok() {
-            for i in 0..context.data.len() - 1 {
-                if context.timeout.reached() {
-                    break 'main;
-                }
+    /// Number of bytes compared by the radix sort.
+    fn digits(&self) -> usize;
 
-                if context.data[i] > context.data[i + 1] {
-                    return Err(Error::Unsorted(context.data));
-                }
-            }
+    /// `i`-th least significant byte, with the item padded to `width` bytes.
+    fn digit(&self, i: usize, width: usize) -> u8;
+}
+
+impl Item for String {
+    fn random<R: Rng + ?Sized>(rng: &mut R, item_len: usize) -> Self {
+        rand::distributions::Alphanumeric.sample_string(rng, item_len)
+    }
+
+    fn digits(&self) -> usize {
+        self.len()
+    }
+
+    fn digit(&self, i: usize, width: usize) -> u8 {
+        // shorter strings are padded at the end, so they sort before their extensions
+        self.as_bytes().get(width - 1 - i).copied().unwrap_or(0)
+    }
+}
+
+impl Item for u32 {
+    fn random<R: Rng + ?Sized>(rng: &mut R, _item_len: usize) -> Self {
+        rng.gen()
+    }
+
+    fn digits(&self) -> usize {
+        4
+    }
+
+    fn digit(&self, i: usize, _width: usize) -> u8 {
+        (self >> (i * 8)) as u8
+    }
+}
+
+impl Item for u64 {
+    fn random<R: Rng + ?Sized>(rng: &mut R, _item_len: usize) -> Self {
+        rng.gen()
+    }
+
+    fn digits(&self) -> usize {
+        8
+    }
+
+    fn digit(&self, i: usize, _width: usize) -> u8 {
+        (self >> (i * 8)) as u8
+    }
+}
+
+impl Item for f64 {
+    fn random<R: Rng + ?Sized>(rng: &mut R, _item_len: usize) -> Self {
+        rng.gen()
+    }
+
+    fn digits(&self) -> usize {
+        8
+    }
+
+    fn digit(&self, i: usize, width: usize) -> u8 {
+        // flip the sign bit of positive and all bits of negative numbers to order them as integers
+        let bits = self.to_bits();
+        let key = match bits >> 63 {
+            0 => bits ^ (1 << 63),
+            _ => !bits,
+        };
+
+        key.digit(i, width)
+    }
+}
+
+/// Fixed-size 64-byte record ordered by its key, the payload is moved along with the key.
+#[derive(Debug, Clone, Copy, Default)]
+struct Record {
+    key: u64,
+    // never read, only copied along with the key
+    #[allow(dead_code)]
+    payload: [u64; 7],
+}
+
+impl PartialEq for Record {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl PartialOrd for Record {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.key.partial_cmp(&other.key)
+    }
+}
+
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.key)
+    }
+}
+
+impl Item for Record {
+    fn random<R: Rng + ?Sized>(rng: &mut R, _item_len: usize) -> Self {
+        Self {
+            key: rng.gen(),
+            payload: rng.gen(),
         }
     }
 
-    Ok(result_builder.build())
+    fn digits(&self) -> usize {
+        self.key.digits()
+    }
+
+    fn digit(&self, i: usize, width: usize) -> u8 {
+        self.key.digit(i, width)
+    }
 }
 
 mod merge {
@@ -136,8 +282,7 @@ mod merge {
         let mut ops = 0;
         timeout.reached_with_err(ops)?;
 
-        if data.len() == 1 {
-            ops += 1;
+        if data.len() <= 1 {
             return Ok(ops);
         }
 
@@ -184,13 +329,15 @@ mod merge {
     where
         T: Clone + PartialOrd,
     {
+        let mut ops = 0;
         let mut d = 0;
         let mut l = 0;
         let mut r = 0;
 
         while l < left.len() && r < right.len() {
-            timeout.reached_with_err(d as u64)?;
+            timeout.reached_with_err(ops)?;
 
+            ops += 1;
             if left[l] <= right[r] {
                 data[d] = left[l].clone();
                 l += 1;
@@ -202,7 +349,7 @@ mod merge {
         }
 
         while l < left.len() {
-            timeout.reached_with_err(d as u64)?;
+            timeout.reached_with_err(ops)?;
 
             data[d] = left[l].clone();
             l += 1;
@@ -210,14 +357,496 @@ mod merge {
         }
 
         while r < right.len() {
-            timeout.reached_with_err(d as u64)?;
+            timeout.reached_with_err(ops)?;
 
             data[d] = right[r].clone();
             r += 1;
             d += 1;
         }
 
-        Ok(d as u64)
+        Ok(ops)
+    }
+}
+
+/// LSD radix sort on bytes, it never compares items and so reports no comparisons.
+mod radix {
+    use rayon::iter::{
+        IndexedParallelIterator, IntoParallelIterator, IntoParallelRefIterator, ParallelIterator,
+    };
+    use rayon::slice::ParallelSliceMut;
+
+    use super::*;
+
+    pub(super) fn run_test<T: Item>(
+        data: &mut [T],
+        temp: &mut [T],
+        timeout: Option<&Timeout>,
+    ) -> Result<u64, u64> {
+        let width = data.iter().map(Item::digits).max().unwrap_or(0);
+
+        lsd(data, temp, width, width, timeout)
+    }
+
+    /// Distributes the items into buckets by their most significant varying byte and sorts
+    /// the buckets in parallel on the remaining bytes.
+    pub(super) fn run_test_multithread<T: Item>(
+        threadpool: &rayon::ThreadPool,
+        data: &mut [T],
+        temp: &mut [T],
+        timeout: Option<&Timeout>,
+    ) -> Result<u64, u64> {
+        threadpool.install(|| {
+            timeout.reached_with_err(0u64)?;
+
+            let width = data.par_iter().map(Item::digits).max().unwrap_or(0);
+
+            let mut counts = [0; 256];
+            let mut digit = width;
+            loop {
+                if digit == 0 {
+                    return Ok(0);
+                }
+
+                digit -= 1;
+                if scatter(data, temp, digit, width, &mut counts) {
+                    break;
+                }
+            }
+
+            split_buckets(temp, &counts)
+                .into_par_iter()
+                .zip(split_buckets(data, &counts))
+                .map(|(bucket, temp)| lsd(bucket, temp, digit, width, timeout))
+                .reduce(|| Ok(0), reduce_ops)?;
+
+            data.par_chunks_mut(CHUNK_LEN)
+                .zip(temp.par_chunks_mut(CHUNK_LEN))
+                .for_each(|(data, temp)| data.swap_with_slice(temp));
+
+            Ok(0)
+        })
+    }
+
+    const CHUNK_LEN: usize = 4096;
+
+    /// Sorts `data` by its `passes` least significant bytes, using `temp` as the scatter target.
+    fn lsd<T: Item>(
+        data: &mut [T],
+        temp: &mut [T],
+        passes: usize,
+        width: usize,
+        timeout: Option<&Timeout>,
+    ) -> Result<u64, u64> {
+        let len = data.len();
+        let mut src = &mut data[..];
+        let mut dst = &mut temp[..len];
+        let mut swapped = false;
+
+        let mut counts = [0; 256];
+        for i in 0..passes {
+            timeout.reached_with_err(0u64)?;
+
+            if scatter(src, dst, i, width, &mut counts) {
+                std::mem::swap(&mut src, &mut dst);
+                swapped = !swapped;
+            }
+        }
+
+        if swapped {
+            dst.swap_with_slice(src);
+        }
+
+        Ok(0)
+    }
+
+    /// Moves the items from `src` to `dst` ordered by their `i`-th byte, leaving the bucket
+    /// sizes in `counts`. Returns `false` without moving anything if all items share the byte.
+    fn scatter<T: Item>(
+        src: &mut [T],
+        dst: &mut [T],
+        i: usize,
+        width: usize,
+        counts: &mut [usize; 256],
+    ) -> bool {
+        counts.fill(0);
+        for item in src.iter() {
+            counts[item.digit(i, width) as usize] += 1;
+        }
+
+        if counts.contains(&src.len()) {
+            return false;
+        }
+
+        let mut offsets = [0; 256];
+        for d in 1..offsets.len() {
+            offsets[d] = offsets[d - 1] + counts[d - 1];
+        }
+
+        for item in src.iter_mut() {
+            let d = item.digit(i, width) as usize;
+            std::mem::swap(&mut dst[offsets[d]], item);
+            offsets[d] += 1;
+        }
+
+        true
+    }
+}
+
+/// Introsort with the pattern-defeating quicksort improvements: ninther pivot selection,
+/// partitioning of items equal to the previous pivot and a heapsort fallback.
+mod quick {
+    use crate::utils::AddValue;
+
+    use super::*;
+
+    const INSERTION_THRESHOLD: usize = 24;
+    const NINTHER_THRESHOLD: usize = 128;
+    const PARALLEL_THRESHOLD: usize = 4096;
+
+    pub(super) fn run_test<T: Item>(data: &mut [T], timeout: Option<&Timeout>) -> Result<u64, u64> {
+        sort(data, limit(data.len()), None, timeout, None)
+    }
+
+    pub(super) fn run_test_multithread<T: Item>(
+        threadpool: &rayon::ThreadPool,
+        data: &mut [T],
+        timeout: Option<&Timeout>,
+    ) -> Result<u64, u64> {
+        threadpool.install(|| sort(data, limit(data.len()), None, timeout, Some(threadpool)))
+    }
+
+    /// Number of unbalanced partitions tolerated before falling back to heapsort.
+    pub(super) fn limit(len: usize) -> u32 {
+        usize::BITS - len.leading_zeros()
+    }
+
+    /// Sorts `data`, all items of which are not less than `pred`.
+    /// Returns the number of comparisons.
+    pub(super) fn sort<T: Item>(
+        data: &mut [T],
+        mut limit: u32,
+        pred: Option<&T>,
+        timeout: Option<&Timeout>,
+        threadpool: Option<&rayon::ThreadPool>,
+    ) -> Result<u64, u64> {
+        let mut ops = 0;
+        timeout.reached_with_err(ops)?;
+
+        let mut is_less = |a: &T, b: &T| {
+            ops += 1;
+            a < b
+        };
+
+        let len = data.len();
+        if len <= INSERTION_THRESHOLD {
+            insertion_sort(data, &mut is_less);
+            return Ok(ops);
+        }
+
+        if limit == 0 {
+            heapsort(data, &mut is_less);
+            return Ok(ops);
+        }
+
+        choose_pivot(data, &mut is_less);
+
+        // the pivot equals the predecessor, so all items equal to it are already in place
+        if pred.is_some_and(|pred| !is_less(pred, &data[0])) {
+            let mid = partition_equal(data, &mut is_less);
+
+            return sort(&mut data[mid..], limit, pred, timeout, threadpool).add(ops);
+        }
+
+        let mid = partition(data, &mut is_less);
+
+        if mid.min(len - mid - 1) < len / 8 {
+            limit -= 1;
+        }
+
+        let (left, right) = data.split_at_mut(mid);
+        let (pivot, right) = right.split_first_mut().unwrap();
+        let pivot = &*pivot;
+
+        match threadpool {
+            Some(_) if len > PARALLEL_THRESHOLD => {
+                let (left_ops, right_ops) = rayon::join(
+                    || sort(left, limit, pred, timeout, threadpool),
+                    || sort(right, limit, Some(pivot), timeout, threadpool),
+                );
+
+                ops = left_ops.add(ops)?;
+                ops = right_ops.add(ops)?;
+            }
+            _ => {
+                ops = sort(left, limit, pred, timeout, threadpool).add(ops)?;
+                ops = sort(right, limit, Some(pivot), timeout, threadpool).add(ops)?;
+            }
+        }
+
+        Ok(ops)
+    }
+
+    fn insertion_sort<T>(data: &mut [T], is_less: &mut impl FnMut(&T, &T) -> bool) {
+        for i in 1..data.len() {
+            let mut j = i;
+            while j > 0 && is_less(&data[j], &data[j - 1]) {
+                data.swap(j, j - 1);
+                j -= 1;
+            }
+        }
+    }
+
+    fn heapsort<T>(data: &mut [T], is_less: &mut impl FnMut(&T, &T) -> bool) {
+        for i in (0..data.len() / 2).rev() {
+            sift_down(data, i, is_less);
+        }
+
+        for end in (1..data.len()).rev() {
+            data.swap(0, end);
+            sift_down(&mut data[..end], 0, is_less);
+        }
+    }
+
+    fn sift_down<T>(data: &mut [T], mut node: usize, is_less: &mut impl FnMut(&T, &T) -> bool) {
+        loop {
+            let mut child = 2 * node + 1;
+            if child >= data.len() {
+                break;
+            }
+
+            if child + 1 < data.len() && is_less(&data[child], &data[child + 1]) {
+                child += 1;
+            }
+
+            if !is_less(&data[node], &data[child]) {
+                break;
+            }
+
+            data.swap(node, child);
+            node = child;
+        }
+    }
+
+    /// Moves the median of three (or of three medians for long slices) to the front.
+    fn choose_pivot<T>(data: &mut [T], is_less: &mut impl FnMut(&T, &T) -> bool) {
+        let len = data.len();
+        let (a, b, c) = (len / 4, len / 2, len / 4 * 3);
+
+        let pivot = if len >= NINTHER_THRESHOLD {
+            let a = median3(data, a - 1, a, a + 1, is_less);
+            let b = median3(data, b - 1, b, b + 1, is_less);
+            let c = median3(data, c - 1, c, c + 1, is_less);
+
+            median3(data, a, b, c, is_less)
+        } else {
+            median3(data, a, b, c, is_less)
+        };
+
+        data.swap(0, pivot);
+    }
+
+    fn median3<T>(
+        data: &[T],
+        a: usize,
+        b: usize,
+        c: usize,
+        is_less: &mut impl FnMut(&T, &T) -> bool,
+    ) -> usize {
+        if is_less(&data[a], &data[b]) {
+            if is_less(&data[b], &data[c]) {
+                b
+            } else if is_less(&data[a], &data[c]) {
+                c
+            } else {
+                a
+            }
+        } else if is_less(&data[a], &data[c]) {
+            a
+        } else if is_less(&data[b], &data[c]) {
+            c
+        } else {
+            b
+        }
+    }
+
+    /// Partitions `data` around the pivot at the front and returns the pivot's new position,
+    /// items before it are less than the pivot.
+    fn partition<T>(data: &mut [T], is_less: &mut impl FnMut(&T, &T) -> bool) -> usize {
+        let (pivot, rest) = data.split_first_mut().unwrap();
+
+        let (mut l, mut r) = (0, rest.len());
+        loop {
+            while l < r && is_less(&rest[l], pivot) {
+                l += 1;
+            }
+            while l < r && !is_less(&rest[r - 1], pivot) {
+                r -= 1;
+            }
+            if l >= r {
+                break;
+            }
+
+            r -= 1;
+            rest.swap(l, r);
+            l += 1;
+        }
+
+        data.swap(0, l);
+        l
+    }
+
+    /// Moves the items equal to the pivot at the front before the greater ones and returns their count.
+    fn partition_equal<T>(data: &mut [T], is_less: &mut impl FnMut(&T, &T) -> bool) -> usize {
+        let (pivot, rest) = data.split_first_mut().unwrap();
+
+        let (mut l, mut r) = (0, rest.len());
+        loop {
+            while l < r && !is_less(pivot, &rest[l]) {
+                l += 1;
+            }
+            while l < r && is_less(pivot, &rest[r - 1]) {
+                r -= 1;
+            }
+            if l >= r {
+                break;
+            }
+
+            r -= 1;
+            rest.swap(l, r);
+            l += 1;
+        }
+
+        l + 1
+    }
+}
+
+/// Distributes the items into buckets delimited by splitters picked from a sample,
+/// then sorts the buckets independently with [quick::sort].
+mod sample {
+    use rayon::iter::{
+        IndexedParallelIterator, IntoParallelIterator, IntoParallelRefIterator, ParallelIterator,
+    };
+    use rayon::slice::ParallelSliceMut;
+
+    use crate::utils::AddValue;
+
+    use super::*;
+
+    /// Power of two, so that every item is classified with `log2(BUCKETS)` comparisons.
+    const BUCKETS: usize = 64;
+    const OVERSAMPLING: usize = 8;
+    const CHUNK_LEN: usize = 4096;
+
+    pub(super) fn run_test<T: Item>(
+        data: &mut [T],
+        temp: &mut [T],
+        timeout: Option<&Timeout>,
+    ) -> Result<u64, u64> {
+        sort(data, temp, timeout, None)
+    }
+
+    pub(super) fn run_test_multithread<T: Item>(
+        threadpool: &rayon::ThreadPool,
+        data: &mut [T],
+        temp: &mut [T],
+        timeout: Option<&Timeout>,
+    ) -> Result<u64, u64> {
+        threadpool.install(|| sort(data, temp, timeout, Some(threadpool)))
+    }
+
+    fn sort<T: Item>(
+        data: &mut [T],
+        temp: &mut [T],
+        timeout: Option<&Timeout>,
+        threadpool: Option<&rayon::ThreadPool>,
+    ) -> Result<u64, u64> {
+        let mut ops = 0;
+        timeout.reached_with_err(ops)?;
+
+        let len = data.len();
+        let samples = BUCKETS * OVERSAMPLING;
+        if len < samples {
+            return quick::sort(data, quick::limit(len), None, timeout, None);
+        }
+
+        let mut sample: Vec<T> = (0..samples)
+            .map(|i| data[i * len / samples].clone())
+            .collect();
+        ops = quick::sort(&mut sample, quick::limit(samples), None, None, None).add(ops)?;
+        let splitters: Vec<T> = sample
+            .into_iter()
+            .skip(OVERSAMPLING)
+            .step_by(OVERSAMPLING)
+            .collect();
+
+        let classes: Vec<u8> = match threadpool {
+            Some(_) => data
+                .par_iter()
+                .map(|item| classify(&splitters, item))
+                .collect(),
+            None => data.iter().map(|item| classify(&splitters, item)).collect(),
+        };
+        ops += len as u64 * BUCKETS.ilog2() as u64;
+        timeout.reached_with_err(ops)?;
+
+        let mut counts = [0; BUCKETS];
+        for &class in classes.iter() {
+            counts[class as usize] += 1;
+        }
+
+        let mut offsets = [0; BUCKETS];
+        for b in 1..offsets.len() {
+            offsets[b] = offsets[b - 1] + counts[b - 1];
+        }
+
+        let temp = &mut temp[..len];
+        for (item, &class) in data.iter_mut().zip(classes.iter()) {
+            let offset = &mut offsets[class as usize];
+            std::mem::swap(&mut temp[*offset], item);
+            *offset += 1;
+        }
+
+        match threadpool {
+            Some(_) => {
+                ops = split_buckets(temp, &counts)
+                    .into_par_iter()
+                    .map(|bucket| {
+                        quick::sort(bucket, quick::limit(bucket.len()), None, timeout, None)
+                    })
+                    .reduce(|| Ok(0), reduce_ops)
+                    .add(ops)?;
+
+                data.par_chunks_mut(CHUNK_LEN)
+                    .zip(temp.par_chunks_mut(CHUNK_LEN))
+                    .for_each(|(data, temp)| data.swap_with_slice(temp));
+            }
+            None => {
+                for bucket in split_buckets(temp, &counts) {
+                    ops = quick::sort(bucket, quick::limit(bucket.len()), None, timeout, None)
+                        .add(ops)?;
+                }
+
+                data.swap_with_slice(temp);
+            }
+        }
+
+        Ok(ops)
+    }
+
+    /// Finds the bucket of `item` with a binary search over the `BUCKETS - 1` sorted splitters.
+    fn classify<T: PartialOrd>(splitters: &[T], item: &T) -> u8 {
+        let (mut bucket, mut len) = (0, splitters.len());
+        while len > 0 {
+            let half = len / 2;
+            if splitters[bucket + half] <= *item {
+                bucket += half + 1;
+                len -= half + 1;
+            } else {
+                len = half;
+            }
+        }
+
+        bucket as u8
     }
 }
 
@@ -226,8 +855,13 @@ pub struct Config {
 
     pub duration: Duration,
 
+    /// Length of the generated strings, ignored by the other data types.
     pub item_len: usize,
     pub data_len: usize,
+    /// Number of inputs generated before the benchmark starts, each run sorts a copy of the next one.
+    pub pool_size: usize,
+    pub data_type: DataType,
+    pub algorithm: Algorithm,
 }
 
 impl Default for Config {
@@ -237,6 +871,59 @@ impl Default for Config {
             duration: Duration::from_secs(10),
             item_len: 25,
             data_len: 100_000,
+            pool_size: 4,
+            data_type: DataType::default(),
+            algorithm: Algorithm::default(),
+        }
+    }
+}
+
+/// Type of the sorted items.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DataType {
+    /// Random alphanumeric strings of `item_len` characters.
+    #[default]
+    String,
+    U32,
+    U64,
+    F64,
+    /// 64-byte records with a `u64` key.
+    Record,
+}
+
+impl fmt::Display for DataType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DataType::String => write!(f, "string"),
+            DataType::U32 => write!(f, "u32"),
+            DataType::U64 => write!(f, "u64"),
+            DataType::F64 => write!(f, "f64"),
+            DataType::Record => write!(f, "record"),
+        }
+    }
+}
+
+/// Sorting algorithm, the multithreaded benchmark runs the parallel variant of each.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Algorithm {
+    /// Top-down merge sort.
+    #[default]
+    Merge,
+    /// Least significant digit radix sort on bytes.
+    Radix,
+    /// In-place pdqsort-style quicksort.
+    Quick,
+    /// Sample sort with quicksorted buckets.
+    Sample,
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Algorithm::Merge => write!(f, "merge"),
+            Algorithm::Radix => write!(f, "radix"),
+            Algorithm::Quick => write!(f, "quick"),
+            Algorithm::Sample => write!(f, "sample"),
         }
     }
 }
@@ -247,72 +934,148 @@ pub enum Error {
 }
 
 pub struct Report {
+    pub data_type: DataType,
+    pub algorithm: Algorithm,
+    /// Time spent in completed sorts.
     pub duration: Duration,
-    pub ops: u64,
-    pub tps: f64,
+    /// Number of items in completed sorts.
+    pub elements: u64,
+    pub comparisons: u64,
+    /// Elements sorted per second.
+    pub eps: f64,
+    /// Comparisons per second.
+    pub cps: f64,
+    /// Time spent generating the input pool before the budget started.
+    pub setup: Duration,
+    /// Time of the budget spent outside of sorting, i.e. copying inputs and verifying results.
+    pub overhead: Duration,
+    /// Share of the elapsed budget spent on the overhead, between 0 and 1.
+    pub overhead_share: f64,
 }
 
 impl fmt::Display for Report {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "sort ... {} ops/s", self.tps.floor())
+        write!(
+            f,
+            "sort ({}, {}) ... {} elements/s, {} comparisons/s, {:.1}% overhead",
+            self.data_type,
+            self.algorithm,
+            self.eps.floor(),
+            self.cps.floor(),
+            self.overhead_share * 100.
+        )
     }
 }
 
 struct ReportBuilder {
+    data_type: DataType,
+    algorithm: Algorithm,
     duration: Duration,
-    ops: u64,
+    elements: u64,
+    comparisons: u64,
+    /// Time spent in all sorts, including the interrupted one.
+    sorting: Duration,
 }
 
 impl ReportBuilder {
-    fn new() -> Self {
+    fn new(data_type: DataType, algorithm: Algorithm) -> Self {
         Self {
+            data_type,
+            algorithm,
             duration: Duration::ZERO,
-            ops: 0,
+            elements: 0,
+            comparisons: 0,
+            sorting: Duration::ZERO,
         }
     }
 
-    fn add(&mut self, duration: Duration, result: Result<u64, u64>) {
-        self.duration += duration;
-        self.ops += result.value();
+    /// Records a sort of `len` items, only completed sorts are counted.
+    fn add(&mut self, duration: Duration, result: Result<u64, u64>, len: usize) {
+        self.sorting += duration;
+
+        if let Ok(comparisons) = result {
+            self.duration += duration;
+            self.elements += len as u64;
+            self.comparisons += comparisons;
+        }
     }
 
-    fn build(self) -> Report {
+    fn per_second(&self, value: u64) -> f64 {
+        match self.duration {
+            Duration::ZERO => 0.,
+            _ => value as f64 / self.duration.as_secs_f64(),
+        }
+    }
+
+    /// Builds the report, `elapsed` is the time spent since the budget started.
+    fn build(self, setup: Duration, elapsed: Duration) -> Report {
+        let overhead = elapsed.saturating_sub(self.sorting);
+
         Report {
+            data_type: self.data_type,
+            algorithm: self.algorithm,
             duration: self.duration,
-            ops: self.ops,
-            tps: match self.duration {
+            elements: self.elements,
+            comparisons: self.comparisons,
+            eps: self.per_second(self.elements),
+            cps: self.per_second(self.comparisons),
+            setup,
+            overhead,
+            overhead_share: match elapsed {
                 Duration::ZERO => 0.,
-                _ => self.ops as f64 / self.duration.as_secs_f64(),
+                _ => overhead.as_secs_f64() / elapsed.as_secs_f64(),
             },
         }
     }
 }
 
-struct Context {
-    rng: Box<dyn rand::RngCore>,
-
-    item_len: usize,
-    data: Vec<String>,
-    temp: Vec<String>,
+struct Context<T> {
+    data_type: DataType,
+    algorithm: Algorithm,
+    /// Inputs generated ahead of the timed runs.
+    pool: Vec<Vec<T>>,
+    round: usize,
+    setup: Duration,
+    data: Vec<T>,
+    temp: Vec<T>,
 
     timeout: Timeout,
 }
 
-impl Context {
-    fn new(config: Config) -> Self {
-        let data = vec![String::new(); config.data_len];
-        let temp = vec![String::new(); config.data_len];
-
+impl<T: Item> Context<T> {
+    fn new(mut config: Config) -> Self {
+        let start = Instant::now();
+        let pool = (0..config.pool_size.max(1))
+            .map(|_| {
+                (0..config.data_len)
+                    .map(|_| T::random(&mut config.rng, config.item_len))
+                    .collect()
+            })
+            .collect();
+        let data = vec![T::default(); config.data_len];
+        let temp = vec![T::default(); config.data_len];
+        let setup = start.elapsed();
+
+        // the budget starts after the inputs are generated
         let timeout = Timeout::new(config.duration);
 
         Self {
-            rng: config.rng,
-            item_len: config.item_len,
+            data_type: config.data_type,
+            algorithm: config.algorithm,
+            pool,
+            round: 0,
+            setup,
             data,
             temp,
             timeout,
         }
     }
+
+    fn reset_data(&mut self) {
+        self.data
+            .clone_from_slice(&self.pool[self.round % self.pool.len()]);
+        self.round += 1;
+    }
 }
 
 #[cfg(test)]
@@ -328,8 +1091,7 @@ mod tests {
         let result = bench(
             &CpuFeatures {
                 num_cores: 1,
-                sve: false,
-                i8mm: false,
+                ..Default::default()
             },
             Config {
                 duration,
@@ -341,9 +1103,14 @@ mod tests {
 
         assert!(result.is_ok(), "expected success");
         let result = result.unwrap();
-        assert!(result.ops > 0);
-        assert!(result.tps > 0.);
-        assert!(elapsed >= duration && elapsed <= duration + Duration::from_millis(200));
+        assert!(result.elements > 0);
+        assert!(result.eps > 0.);
+        assert!(result.cps > 0.);
+        assert!(result.overhead_share >= 0. && result.overhead_share < 1.);
+        assert!(
+            elapsed >= duration + result.setup
+                && elapsed <= duration + result.setup + Duration::from_millis(200)
+        );
 
         println!("{result}");
     }
@@ -355,8 +1122,7 @@ mod tests {
         let result = bench_multithread(
             &CpuFeatures {
                 num_cores: 8,
-                sve: false,
-                i8mm: false,
+                ..Default::default()
             },
             Config {
                 duration,
@@ -368,13 +1134,100 @@ mod tests {
 
         assert!(result.is_ok(), "expected success");
         let result = result.unwrap();
-        assert!(result.ops > 0);
-        assert!(result.tps > 0.);
-        assert!(elapsed >= duration && elapsed <= duration + Duration::from_millis(200));
+        assert!(result.elements > 0);
+        assert!(result.eps > 0.);
+        assert!(result.cps > 0.);
+        assert!(result.overhead_share >= 0. && result.overhead_share < 1.);
+        assert!(
+            elapsed >= duration + result.setup
+                && elapsed <= duration + result.setup + Duration::from_millis(200)
+        );
 
         println!("{result}");
     }
 
+    #[test]
+    fn test_bench_data_types() {
+        for data_type in [
+            DataType::String,
+            DataType::U32,
+            DataType::U64,
+            DataType::F64,
+            DataType::Record,
+        ] {
+            let result = bench(
+                &CpuFeatures::default(),
+                Config {
+                    duration: Duration::from_millis(200),
+                    data_len: 10_000,
+                    data_type,
+                    ..Default::default()
+                },
+            );
+
+            assert!(result.is_ok(), "expected success");
+            let result = result.unwrap();
+            assert_eq!(data_type, result.data_type);
+            assert!(result.eps > 0.);
+
+            println!("{result}");
+        }
+    }
+
+    #[test]
+    fn test_bench_algorithms() {
+        for algorithm in [
+            Algorithm::Merge,
+            Algorithm::Radix,
+            Algorithm::Quick,
+            Algorithm::Sample,
+        ] {
+            for data_type in [DataType::String, DataType::F64] {
+                let features = CpuFeatures {
+                    num_cores: 4,
+                    ..Default::default()
+                };
+                let config = || Config {
+                    duration: Duration::from_millis(200),
+                    data_len: 10_000,
+                    data_type,
+                    algorithm,
+                    ..Default::default()
+                };
+
+                for result in [
+                    bench(&features, config()),
+                    bench_multithread(&features, config()),
+                ] {
+                    assert!(result.is_ok(), "expected success");
+                    let result = result.unwrap();
+                    assert_eq!(algorithm, result.algorithm);
+                    assert!(result.eps > 0.);
+
+                    println!("{result}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_reset_data() {
+        let mut context = Context::<u64>::new(Config {
+            duration: Duration::ZERO,
+            data_len: 100,
+            pool_size: 2,
+            ..Default::default()
+        });
+
+        context.reset_data();
+        assert_eq!(context.pool[0], context.data);
+        context.data.sort();
+        context.reset_data();
+        assert_eq!(context.pool[1], context.data);
+        context.reset_data();
+        assert_eq!(context.pool[0], context.data);
+    }
+
     #[test]
     fn test_merge() {
         let mut data = [19, 72, 4, 86, 44, 7, 100, 79, 100, 99, 27, 12, 81, 46, 32];
@@ -384,7 +1237,7 @@ mod tests {
         let result = merge::run_test(&mut data, &mut temp, None);
 
         assert!(result.is_ok(), "expected success");
-        assert_eq!(74, result.unwrap());
+        assert_eq!(43, result.unwrap());
         assert_eq!(expected, data);
     }
 
@@ -401,7 +1254,70 @@ mod tests {
         let result = merge::run_test_multithread(&threadpool, &mut data, &mut temp, None);
 
         assert!(result.is_ok(), "expected success");
-        assert_eq!(74, result.unwrap());
+        assert_eq!(43, result.unwrap());
+        assert_eq!(expected, data);
+    }
+
+    #[test]
+    fn test_radix() {
+        let mut data = ["b", "ab", "", "a", "ba", "abc", "aa"].map(String::from);
+        let mut temp = vec![String::new(); data.len()];
+        let expected = ["", "a", "aa", "ab", "abc", "b", "ba"].map(String::from);
+
+        let result = radix::run_test(&mut data, &mut temp, None);
+
+        assert!(result.is_ok(), "expected success");
+        assert_eq!(0, result.unwrap());
+        assert_eq!(expected, data);
+
+        let mut data = [0.5, -1.5, 0., 3.25, -0.25, 100., -100.];
+        let mut temp = [0.; 7];
+        let expected = [-100., -1.5, -0.25, 0., 0.5, 3.25, 100.];
+
+        let result = radix::run_test(&mut data, &mut temp, None);
+
+        assert!(result.is_ok(), "expected success");
         assert_eq!(expected, data);
     }
+
+    #[test]
+    fn test_quick() {
+        let mut data: [u32; 15] = [19, 72, 4, 86, 44, 7, 100, 79, 100, 99, 27, 12, 81, 46, 32];
+        let expected = [4, 7, 12, 19, 27, 32, 44, 46, 72, 79, 81, 86, 99, 100, 100];
+
+        let result = quick::run_test(&mut data, None);
+
+        assert!(result.is_ok(), "expected success");
+        assert_eq!(61, result.unwrap());
+        assert_eq!(expected, data);
+    }
+
+    #[test]
+    fn test_algorithms_random() {
+        let threadpool = rayon::ThreadPoolBuilder::new()
+            .num_threads(2)
+            .build()
+            .unwrap();
+        let mut rng = rand::thread_rng();
+
+        for algorithm in [
+            Algorithm::Merge,
+            Algorithm::Radix,
+            Algorithm::Quick,
+            Algorithm::Sample,
+        ] {
+            for threadpool in [None, Some(&threadpool)] {
+                // few distinct values to exercise the handling of equal items
+                let mut data: Vec<u64> = (0..20_000).map(|_| rng.gen_range(0..64)).collect();
+                let mut temp = vec![0; data.len()];
+                let mut expected = data.clone();
+                expected.sort();
+
+                let result = sort(algorithm, threadpool, &mut data, &mut temp, None);
+
+                assert!(result.is_ok(), "expected success");
+                assert_eq!(expected, data, "{algorithm} unsorted");
+            }
+        }
+    }
 }
@@ -0,0 +1,351 @@
+#![allow(clippy::needless_range_loop)]
+
+use rand::Rng;
+
+use crate::utils::{adc, mac, sbb};
+
+/// Base field modulus q, little-endian limbs.
+const MODULUS: [u64; 4] = [
+    0x3c208c16d87cfd47,
+    0x97816a916871ca8d,
+    0xb85045b68181585d,
+    0x30644e72e131a029,
+];
+/// -q^(-1) mod 2^64
+const INV: u64 = 0x87d20782e4866389;
+/// 2^256 mod q
+const R: [u64; 4] = [
+    0xd35d438dc58f0d9d,
+    0x0a78eb28f5c70b3d,
+    0x666ea36f7879462c,
+    0x0e0a77c19a07df2f,
+];
+/// 2^512 mod q
+const R2: [u64; 4] = [
+    0xf32cfc5b538afa89,
+    0xb5e71911d44501fb,
+    0x47ab1eff0a417ff6,
+    0x06d89f71cab8351f,
+];
+
+/// Bit length of the scalar field order r.
+pub(crate) const SCALAR_BITS: usize = 254;
+
+/// Field operations (multiplications, squarings, additions and subtractions)
+/// performed by [G1::add] and [G1::double] in the general case.
+pub(crate) const ADD_OPS: u64 = 29;
+pub(crate) const DOUBLE_OPS: u64 = 21;
+
+/// Scalar as little-endian limbs, not reduced modulo r.
+pub(crate) type Scalar = [u64; 4];
+
+pub(crate) fn random_scalar<R: Rng + ?Sized>(rng: &mut R) -> Scalar {
+    let mut scalar: Scalar = rng.gen();
+    // keep it below r
+    scalar[3] &= 0x0fff_ffff_ffff_ffff;
+
+    scalar
+}
+
+/// Element of the BN254 base field in Montgomery form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Fq([u64; 4]);
+
+impl Fq {
+    const ZERO: Self = Self([0; 4]);
+    const ONE: Self = Self(R);
+
+    pub(crate) fn from_raw(limbs: [u64; 4]) -> Self {
+        Self(limbs).mul(&Self(R2))
+    }
+
+    #[cfg(test)]
+    fn to_raw(self) -> [u64; 4] {
+        self.mul(&Self([1, 0, 0, 0])).0
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0 == [0; 4]
+    }
+
+    fn add(&self, rhs: &Self) -> Self {
+        let mut carry = 0;
+        let mut sum = [0u64; 4];
+        for i in 0..4 {
+            (sum[i], carry) = adc(self.0[i], rhs.0[i], carry);
+        }
+
+        // q < 2^254, the sum never overflows 256 bits
+        Self(sub_modulus_if_greater(sum))
+    }
+
+    fn double(&self) -> Self {
+        self.add(self)
+    }
+
+    fn sub(&self, rhs: &Self) -> Self {
+        let mut borrow = 0;
+        let mut diff = [0u64; 4];
+        for i in 0..4 {
+            (diff[i], borrow) = sbb(self.0[i], rhs.0[i], borrow);
+        }
+
+        if borrow != 0 {
+            let mut carry = 0;
+            for i in 0..4 {
+                (diff[i], carry) = adc(diff[i], MODULUS[i], carry);
+            }
+        }
+
+        Self(diff)
+    }
+
+    /// Montgomery multiplication (CIOS).
+    fn mul(&self, rhs: &Self) -> Self {
+        let mut t = [0u64; 6];
+        for i in 0..4 {
+            let mut carry = 0;
+            for j in 0..4 {
+                (t[j], carry) = mac(t[j], self.0[j], rhs.0[i], carry);
+            }
+            (t[4], t[5]) = adc(t[4], carry, 0);
+
+            let m = t[0].wrapping_mul(INV);
+            let (_, mut carry) = mac(t[0], m, MODULUS[0], 0);
+            for j in 1..4 {
+                (t[j - 1], carry) = mac(t[j], m, MODULUS[j], carry);
+            }
+            (t[3], carry) = adc(t[4], carry, 0);
+            t[4] = t[5] + carry;
+        }
+
+        Self(sub_modulus_if_greater([t[0], t[1], t[2], t[3]]))
+    }
+
+    fn square(&self) -> Self {
+        self.mul(self)
+    }
+}
+
+fn sub_modulus_if_greater(value: [u64; 4]) -> [u64; 4] {
+    let mut borrow = 0;
+    let mut diff = [0u64; 4];
+    for i in 0..4 {
+        (diff[i], borrow) = sbb(value[i], MODULUS[i], borrow);
+    }
+
+    if borrow != 0 {
+        value
+    } else {
+        diff
+    }
+}
+
+/// Point on `y^2 = x^3 + 3` in Jacobian coordinates, `z == 0` is the point at infinity.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct G1 {
+    x: Fq,
+    y: Fq,
+    z: Fq,
+}
+
+impl G1 {
+    pub(crate) const INFINITY: Self = Self {
+        x: Fq::ZERO,
+        y: Fq::ONE,
+        z: Fq::ZERO,
+    };
+
+    pub(crate) fn generator() -> Self {
+        Self {
+            x: Fq::from_raw([1, 0, 0, 0]),
+            y: Fq::from_raw([2, 0, 0, 0]),
+            z: Fq::ONE,
+        }
+    }
+
+    pub(crate) fn is_infinity(&self) -> bool {
+        self.z.is_zero()
+    }
+
+    pub(crate) fn is_on_curve(&self) -> bool {
+        if self.is_infinity() {
+            return true;
+        }
+
+        // y^2 = x^3 + 3 z^6
+        let z2 = self.z.square();
+        let z6 = z2.square().mul(&z2);
+        let b = Fq::from_raw([3, 0, 0, 0]);
+
+        self.y.square() == self.x.square().mul(&self.x).add(&b.mul(&z6))
+    }
+
+    /// dbl-2009-l
+    pub(crate) fn double(&self) -> Self {
+        if self.is_infinity() {
+            return *self;
+        }
+
+        let a = self.x.square();
+        let b = self.y.square();
+        let c = b.square();
+        let d = self.x.add(&b).square().sub(&a).sub(&c).double();
+        let e = a.double().add(&a);
+        let f = e.square();
+        let x = f.sub(&d.double());
+        let y = e.mul(&d.sub(&x)).sub(&c.double().double().double());
+        let z = self.y.mul(&self.z).double();
+
+        Self { x, y, z }
+    }
+
+    /// add-2007-bl
+    pub(crate) fn add(&self, rhs: &Self) -> Self {
+        if self.is_infinity() {
+            return *rhs;
+        }
+        if rhs.is_infinity() {
+            return *self;
+        }
+
+        let z1z1 = self.z.square();
+        let z2z2 = rhs.z.square();
+        let u1 = self.x.mul(&z2z2);
+        let u2 = rhs.x.mul(&z1z1);
+        let s1 = self.y.mul(&rhs.z).mul(&z2z2);
+        let s2 = rhs.y.mul(&self.z).mul(&z1z1);
+
+        let h = u2.sub(&u1);
+        let r = s2.sub(&s1).double();
+        if h.is_zero() {
+            return if r.is_zero() {
+                self.double()
+            } else {
+                Self::INFINITY
+            };
+        }
+
+        let i = h.double().square();
+        let j = h.mul(&i);
+        let v = u1.mul(&i);
+        let x = r.square().sub(&j).sub(&v.double());
+        let y = r.mul(&v.sub(&x)).sub(&s1.mul(&j).double());
+        let z = self.z.add(&rhs.z).square().sub(&z1z1).sub(&z2z2).mul(&h);
+
+        Self { x, y, z }
+    }
+
+    pub(crate) fn mul(&self, scalar: &Scalar) -> Self {
+        let mut acc = Self::INFINITY;
+        for bit in (0..SCALAR_BITS).rev() {
+            acc = acc.double();
+            if (scalar[bit / 64] >> (bit % 64)) & 1 == 1 {
+                acc = acc.add(self);
+            }
+        }
+
+        acc
+    }
+}
+
+impl PartialEq for G1 {
+    fn eq(&self, other: &Self) -> bool {
+        match (self.is_infinity(), other.is_infinity()) {
+            (true, true) => true,
+            (false, false) => {
+                // x1 * z2^2 == x2 * z1^2 and y1 * z2^3 == y2 * z1^3
+                let z1z1 = self.z.square();
+                let z2z2 = other.z.square();
+
+                self.x.mul(&z2z2) == other.x.mul(&z1z1)
+                    && self.y.mul(&z2z2).mul(&other.z) == other.y.mul(&z1z1).mul(&self.z)
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn affine(x: [u64; 4], y: [u64; 4]) -> G1 {
+        G1 {
+            x: Fq::from_raw(x),
+            y: Fq::from_raw(y),
+            z: Fq::ONE,
+        }
+    }
+
+    #[test]
+    fn test_fq_mul() {
+        let a = Fq::from_raw([7, 0, 0, 0]);
+        let b = Fq::from_raw([6, 0, 0, 0]);
+
+        assert_eq!([42, 0, 0, 0], a.mul(&b).to_raw());
+        assert_eq!([1, 0, 0, 0], Fq::ONE.to_raw());
+
+        let minus_one = Fq::ZERO.sub(&Fq::ONE);
+        assert_eq!(Fq::ONE, minus_one.square());
+        assert_eq!(Fq::ZERO, minus_one.add(&Fq::ONE));
+    }
+
+    #[test]
+    fn test_double() {
+        let expected = affine(
+            [
+                0xd3c208c16d87cfd3,
+                0xd97816a916871ca8,
+                0x9b85045b68181585,
+                0x030644e72e131a02,
+            ],
+            [
+                0xff3ebf7a5a18a2c4,
+                0x68a6a449e3538fc7,
+                0xe7845f96b2ae9c0a,
+                0x15ed738c0e0a7c92,
+            ],
+        );
+
+        let g = G1::generator();
+
+        assert!(g.is_on_curve());
+        assert_eq!(expected, g.double());
+        assert_eq!(expected, g.add(&g));
+        assert!(g.double().is_on_curve());
+    }
+
+    #[test]
+    fn test_mul() {
+        let expected = affine(
+            [
+                0x02fccb202733f347,
+                0x67634b28eca01842,
+                0x48cc66d988b99885,
+                0x2148c698345b7d98,
+            ],
+            [
+                0x078da20dc2455a19,
+                0x20da0452ddb09511,
+                0x083557292655cd98,
+                0x11c1266751992bb9,
+            ],
+        );
+
+        // 2^253 + 12345
+        let scalar = [12345, 0, 0, 1 << 61];
+        let result = G1::generator().mul(&scalar);
+
+        assert!(result.is_on_curve());
+        assert_eq!(expected, result);
+
+        let order = [
+            0x43e1f593f0000001,
+            0x2833e84879b97091,
+            0xb85045b68181585d,
+            0x30644e72e131a029,
+        ];
+        assert!(G1::generator().mul(&order).is_infinity());
+    }
+}
@@ -0,0 +1,133 @@
+use rand::Rng;
+
+/// p = 2^64 - 2^32 + 1
+const MODULUS: u64 = 0xffff_ffff_0000_0001;
+/// 2^64 mod p
+const EPSILON: u64 = 0xffff_ffff;
+
+pub(crate) const TWO_ADICITY: u32 = 32;
+/// 7^((p - 1) / 2^32), a primitive 2^32-th root of unity
+const TWO_ADIC_ROOT: u64 = 0x1856_29dc_da58_878c;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct Fp(u64);
+
+impl Fp {
+    pub(crate) const ZERO: Self = Self(0);
+    pub(crate) const ONE: Self = Self(1);
+
+    pub(crate) fn random<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        Self(rng.gen_range(0..MODULUS))
+    }
+
+    /// Primitive root of unity of order `2^log_n`.
+    pub(crate) fn root_of_unity(log_n: u32) -> Self {
+        assert!(log_n <= TWO_ADICITY);
+
+        let mut root = Self(TWO_ADIC_ROOT);
+        for _ in log_n..TWO_ADICITY {
+            root = root.mul(root);
+        }
+
+        root
+    }
+
+    pub(crate) fn add(self, rhs: Self) -> Self {
+        let (sum, overflow) = self.0.overflowing_add(rhs.0);
+        if overflow || sum >= MODULUS {
+            Self(sum.wrapping_sub(MODULUS))
+        } else {
+            Self(sum)
+        }
+    }
+
+    pub(crate) fn sub(self, rhs: Self) -> Self {
+        let (diff, borrow) = self.0.overflowing_sub(rhs.0);
+        if borrow {
+            Self(diff.wrapping_add(MODULUS))
+        } else {
+            Self(diff)
+        }
+    }
+
+    pub(crate) fn mul(self, rhs: Self) -> Self {
+        Self(reduce128(self.0 as u128 * rhs.0 as u128))
+    }
+
+    pub(crate) fn pow(self, mut exp: u64) -> Self {
+        let mut base = self;
+        let mut acc = Self::ONE;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                acc = acc.mul(base);
+            }
+            base = base.mul(base);
+            exp >>= 1;
+        }
+
+        acc
+    }
+}
+
+/// Reduces `x = lo + hi_lo * 2^64 + hi_hi * 2^96` using `2^64 = 2^32 - 1` and `2^96 = -1` (mod p).
+fn reduce128(x: u128) -> u64 {
+    let lo = x as u64;
+    let hi = (x >> 64) as u64;
+    let hi_hi = hi >> 32;
+    let hi_lo = hi & EPSILON;
+
+    let (mut t0, borrow) = lo.overflowing_sub(hi_hi);
+    if borrow {
+        t0 = t0.wrapping_sub(EPSILON);
+    }
+
+    let t1 = hi_lo * EPSILON;
+    let (mut t2, carry) = t0.overflowing_add(t1);
+    if carry {
+        t2 = t2.wrapping_add(EPSILON);
+    }
+
+    if t2 >= MODULUS {
+        t2 - MODULUS
+    } else {
+        t2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mul() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..1000 {
+            let a = Fp::random(&mut rng);
+            let b = Fp::random(&mut rng);
+            let expected = (a.0 as u128 * b.0 as u128 % MODULUS as u128) as u64;
+
+            assert_eq!(expected, a.mul(b).0);
+        }
+
+        let max = Fp(MODULUS - 1);
+        assert_eq!(Fp::ONE, max.mul(max));
+    }
+
+    #[test]
+    fn test_add_sub() {
+        let max = Fp(MODULUS - 1);
+
+        assert_eq!(Fp::ZERO, max.add(Fp::ONE));
+        assert_eq!(max, Fp::ZERO.sub(Fp::ONE));
+        assert_eq!(Fp(MODULUS - 3), max.add(max).sub(Fp::ONE));
+    }
+
+    #[test]
+    fn test_root_of_unity() {
+        let root = Fp::root_of_unity(10);
+
+        assert_eq!(Fp::ONE, root.pow(1 << 10));
+        assert_ne!(Fp::ONE, root.pow(1 << 9));
+        assert_eq!(Fp::ONE, root.mul(root.pow(MODULUS - 2)));
+    }
+}
@@ -0,0 +1,695 @@
+use std::{
+    fmt,
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use rand::Rng;
+
+use crate::{
+    utils::{Expirable, GetValue, Timeout},
+    CpuFeatures,
+};
+
+use bn254::{random_scalar, Scalar, G1};
+use goldilocks::Fp;
+
+mod bn254;
+mod goldilocks;
+
+pub(crate) fn bench(_features: &CpuFeatures, config: Config) -> Result<Report, Error> {
+    let mut context = Context::new(config, 1)?;
+    let mut report_builder = ReportBuilder::new();
+
+    let mut start: Instant;
+    while !context.timeout.reached() {
+        context.reset_ntt_data();
+
+        start = Instant::now();
+        let ops = black_box(ntt::run_test(
+            &mut context.ntt_data[..],
+            &context.twiddles[..],
+            Some(&context.timeout),
+        ));
+
+        report_builder.add_ntt(start.elapsed(), ops);
+
+        if ops.is_ok() {
+            context.verify_ntt()?;
+        }
+
+        if context.timeout.reached() {
+            break;
+        }
+
+        context.reset_scalars();
+
+        start = Instant::now();
+        let ops = black_box(msm::run_test(
+            &context.bases[..],
+            &context.scalars[..],
+            &mut context.msm_result,
+            Some(&context.timeout),
+        ));
+
+        report_builder.add_msm(start.elapsed(), ops);
+
+        if ops.is_ok() && !context.msm_result.is_on_curve() {
+            return Err(Error::MsmNotOnCurve);
+        }
+    }
+
+    Ok(report_builder.build())
+}
+
+pub(crate) fn bench_multithread(features: &CpuFeatures, config: Config) -> Result<Report, Error> {
+    let mut context = Context::new(config, features.num_cores)?;
+    let threadpool = rayon::ThreadPoolBuilder::new()
+        .num_threads(features.num_cores)
+        .build()
+        .unwrap();
+    let mut report_builder = ReportBuilder::new();
+
+    let mut start: Instant;
+    while !context.timeout.reached() {
+        context.reset_ntt_data();
+
+        start = Instant::now();
+        let ops = black_box(ntt::run_test_multithread(
+            &threadpool,
+            &mut context.ntt_data[..],
+            &context.twiddles[..],
+            Some(&context.timeout),
+        ));
+
+        report_builder.add_ntt(start.elapsed(), ops);
+
+        if ops.is_ok() {
+            context.verify_ntt()?;
+        }
+
+        if context.timeout.reached() {
+            break;
+        }
+
+        context.reset_scalars();
+
+        start = Instant::now();
+        let ops = black_box(msm::run_test_multithread(
+            &threadpool,
+            &context.bases[..],
+            &context.scalars[..],
+            &mut context.msm_result,
+            Some(&context.timeout),
+        ));
+
+        report_builder.add_msm(start.elapsed(), ops);
+
+        if ops.is_ok() && !context.msm_result.is_on_curve() {
+            return Err(Error::MsmNotOnCurve);
+        }
+    }
+
+    Ok(report_builder.build())
+}
+
+fn merge_results(acc: Result<u64, u64>, next: Result<u64, u64>) -> Result<u64, u64> {
+    match (acc, next) {
+        (Ok(acc), Ok(next)) => Ok(acc + next),
+        _ => Err(acc.value() + next.value()),
+    }
+}
+
+/// Radix-2 number-theoretic transform over the Goldilocks field.
+mod ntt {
+    use rayon::{iter::ParallelIterator, slice::ParallelSliceMut};
+
+    use super::*;
+
+    /// Field operations performed by a single butterfly: one multiplication, one addition
+    /// and one subtraction.
+    const BUTTERFLY_OPS: u64 = 3;
+
+    pub(super) fn run_test(
+        data: &mut [Fp],
+        twiddles: &[Fp],
+        timeout: Option<&Timeout>,
+    ) -> Result<u64, u64> {
+        forward(data, twiddles, timeout)
+    }
+
+    pub(super) fn run_test_multithread(
+        threadpool: &rayon::ThreadPool,
+        data: &mut [Fp],
+        twiddles: &[Fp],
+        timeout: Option<&Timeout>,
+    ) -> Result<u64, u64> {
+        let n = twiddles.len() * 2;
+
+        threadpool.install(|| {
+            data.par_chunks_mut(n)
+                .map(|poly| forward(poly, twiddles, timeout))
+                .reduce(|| Ok(0), merge_results)
+        })
+    }
+
+    /// In-place forward transform of `data`, with `twiddles[k] = w^k` for `k < n / 2`.
+    pub(super) fn forward(
+        data: &mut [Fp],
+        twiddles: &[Fp],
+        timeout: Option<&Timeout>,
+    ) -> Result<u64, u64> {
+        let n = data.len();
+        let mut ops = 0;
+
+        bit_reverse(data);
+
+        let mut len = 2;
+        while len <= n {
+            timeout.reached_with_err(ops)?;
+
+            let half = len / 2;
+            let stride = n / len;
+            for block in data.chunks_mut(len) {
+                let (lo, hi) = block.split_at_mut(half);
+                for j in 0..half {
+                    let u = lo[j];
+                    let v = hi[j].mul(twiddles[j * stride]);
+                    lo[j] = u.add(v);
+                    hi[j] = u.sub(v);
+                }
+            }
+            ops += (n / 2) as u64 * BUTTERFLY_OPS;
+
+            len <<= 1;
+        }
+
+        Ok(ops)
+    }
+
+    /// Evaluates the polynomial with `coeffs` at `point`, the naive reference of a single
+    /// transform output.
+    pub(super) fn evaluate(coeffs: &[Fp], point: Fp) -> Fp {
+        coeffs
+            .iter()
+            .rev()
+            .fold(Fp::ZERO, |acc, &coeff| acc.mul(point).add(coeff))
+    }
+
+    fn bit_reverse(data: &mut [Fp]) {
+        let n = data.len();
+        let shift = usize::BITS - n.trailing_zeros();
+        for i in 0..n {
+            let j = i.reverse_bits() >> shift;
+            if i < j {
+                data.swap(i, j);
+            }
+        }
+    }
+}
+
+/// Multi-scalar multiplication over BN254 G1 using Pippenger's bucket method.
+mod msm {
+    use rayon::{
+        iter::{IndexedParallelIterator, ParallelIterator},
+        slice::ParallelSlice,
+    };
+
+    use super::*;
+
+    pub(super) fn run_test(
+        bases: &[G1],
+        scalars: &[Scalar],
+        result: &mut G1,
+        timeout: Option<&Timeout>,
+    ) -> Result<u64, u64> {
+        pippenger(bases, scalars, result, timeout)
+    }
+
+    pub(super) fn run_test_multithread(
+        threadpool: &rayon::ThreadPool,
+        bases: &[G1],
+        scalars: &[Scalar],
+        result: &mut G1,
+        timeout: Option<&Timeout>,
+    ) -> Result<u64, u64> {
+        let chunk_size = bases.len().div_ceil(threadpool.current_num_threads());
+        let (partial, ops) = threadpool.install(|| {
+            bases
+                .par_chunks(chunk_size)
+                .zip(scalars.par_chunks(chunk_size))
+                .map(|(bases, scalars)| {
+                    let mut partial = G1::INFINITY;
+                    let ops = pippenger(bases, scalars, &mut partial, timeout);
+
+                    (partial, ops)
+                })
+                .reduce(
+                    || (G1::INFINITY, Ok(0)),
+                    |(acc, acc_ops), (next, next_ops)| {
+                        (acc.add(&next), merge_results(acc_ops, next_ops))
+                    },
+                )
+        });
+
+        *result = partial;
+
+        // the partial results are summed up in the reduction
+        let num_chunks = bases.len().div_ceil(chunk_size) as u64;
+        ops.map(|ops| ops + num_chunks.saturating_sub(1) * bn254::ADD_OPS)
+    }
+
+    pub(super) fn pippenger(
+        bases: &[G1],
+        scalars: &[Scalar],
+        result: &mut G1,
+        timeout: Option<&Timeout>,
+    ) -> Result<u64, u64> {
+        let mut ops = 0;
+        *result = G1::INFINITY;
+
+        let c = window_size(bases.len());
+        let num_windows = bn254::SCALAR_BITS.div_ceil(c);
+        let mut buckets = vec![G1::INFINITY; (1 << c) - 1];
+
+        for w in (0..num_windows).rev() {
+            timeout.reached_with_err(ops)?;
+
+            for _ in 0..c {
+                *result = result.double();
+            }
+            ops += c as u64 * bn254::DOUBLE_OPS;
+
+            buckets.fill(G1::INFINITY);
+            for (base, scalar) in bases.iter().zip(scalars) {
+                let digit = window(scalar, w * c, c);
+                if digit != 0 {
+                    buckets[digit - 1] = buckets[digit - 1].add(base);
+                    ops += bn254::ADD_OPS;
+                }
+            }
+
+            timeout.reached_with_err(ops)?;
+
+            // sum_i i * buckets[i - 1]
+            let mut running = G1::INFINITY;
+            let mut sum = G1::INFINITY;
+            for bucket in buckets.iter().rev() {
+                running = running.add(bucket);
+                sum = sum.add(&running);
+            }
+            ops += 2 * buckets.len() as u64 * bn254::ADD_OPS;
+
+            *result = result.add(&sum);
+            ops += bn254::ADD_OPS;
+        }
+
+        Ok(ops)
+    }
+
+    /// Naive reference: double-and-add every base separately.
+    #[cfg(test)]
+    pub(super) fn naive(bases: &[G1], scalars: &[Scalar]) -> G1 {
+        bases
+            .iter()
+            .zip(scalars)
+            .fold(G1::INFINITY, |acc, (base, scalar)| {
+                acc.add(&base.mul(scalar))
+            })
+    }
+
+    fn window_size(n: usize) -> usize {
+        if n < 32 {
+            3
+        } else {
+            (n.ilog2() as usize * 69 / 100) + 2
+        }
+    }
+
+    fn window(scalar: &Scalar, offset: usize, c: usize) -> usize {
+        let limb = offset / 64;
+        let shift = offset % 64;
+
+        let mut bits = scalar[limb] >> shift;
+        if shift + c > 64 && limb + 1 < scalar.len() {
+            bits |= scalar[limb + 1] << (64 - shift);
+        }
+
+        (bits & ((1 << c) - 1)) as usize
+    }
+}
+
+pub struct Config {
+    pub rng: Box<dyn rand::RngCore>,
+
+    pub duration: Duration,
+
+    /// NTT size as a power of two, at most 32.
+    pub ntt_log_n: u32,
+    /// Number of (base, scalar) pairs in the MSM.
+    pub msm_n: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            rng: Box::new(rand::thread_rng()),
+            duration: Duration::from_secs(10),
+            ntt_log_n: 16,
+            msm_n: 1 << 12,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    /// The configured NTT size exceeds the largest power-of-two subgroup of the field.
+    NttSizeTooLarge(u32),
+    NttMismatch(usize),
+    MsmNotOnCurve,
+}
+
+pub struct Report {
+    pub ntt_duration: Duration,
+    pub ntt_ops: u64,
+    pub ntt_tps: f64,
+
+    pub msm_duration: Duration,
+    pub msm_ops: u64,
+    pub msm_tps: f64,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "zk ... ntt {} field ops/s, msm {} field ops/s",
+            self.ntt_tps.floor(),
+            self.msm_tps.floor()
+        )
+    }
+}
+
+struct ReportBuilder {
+    ntt_duration: Duration,
+    ntt_ops: u64,
+
+    msm_duration: Duration,
+    msm_ops: u64,
+}
+
+impl ReportBuilder {
+    fn new() -> Self {
+        Self {
+            ntt_duration: Duration::ZERO,
+            ntt_ops: 0,
+            msm_duration: Duration::ZERO,
+            msm_ops: 0,
+        }
+    }
+
+    fn add_ntt(&mut self, duration: Duration, result: Result<u64, u64>) {
+        self.ntt_duration += duration;
+        self.ntt_ops += result.value();
+    }
+
+    fn add_msm(&mut self, duration: Duration, result: Result<u64, u64>) {
+        self.msm_duration += duration;
+        self.msm_ops += result.value();
+    }
+
+    fn build(self) -> Report {
+        Report {
+            ntt_duration: self.ntt_duration,
+            ntt_ops: self.ntt_ops,
+            ntt_tps: tps(self.ntt_duration, self.ntt_ops),
+            msm_duration: self.msm_duration,
+            msm_ops: self.msm_ops,
+            msm_tps: tps(self.msm_duration, self.msm_ops),
+        }
+    }
+}
+
+fn tps(duration: Duration, ops: u64) -> f64 {
+    match duration {
+        Duration::ZERO => 0.,
+        _ => ops as f64 / duration.as_secs_f64(),
+    }
+}
+
+struct Context {
+    rng: Box<dyn rand::RngCore>,
+
+    n: usize,
+    root: Fp,
+    twiddles: Vec<Fp>,
+    ntt_input: Vec<Fp>,
+    ntt_data: Vec<Fp>,
+
+    bases: Vec<G1>,
+    scalars: Vec<Scalar>,
+    msm_result: G1,
+
+    timeout: Timeout,
+}
+
+impl Context {
+    fn new(mut config: Config, batch: usize) -> Result<Self, Error> {
+        if config.ntt_log_n > goldilocks::TWO_ADICITY {
+            return Err(Error::NttSizeTooLarge(config.ntt_log_n));
+        }
+        let ntt_log_n = config.ntt_log_n.max(1);
+        let msm_n = config.msm_n.max(1);
+
+        let n = 1 << ntt_log_n;
+        let root = Fp::root_of_unity(ntt_log_n);
+        let twiddles = (0..n / 2)
+            .scan(Fp::ONE, |w, _| {
+                let current = *w;
+                *w = w.mul(root);
+
+                Some(current)
+            })
+            .collect();
+
+        let ntt_input = vec![Fp::ZERO; n * batch];
+        let ntt_data = vec![Fp::ZERO; n * batch];
+
+        // consecutive multiples of a random point, cheaper to generate than independent points
+        let start = G1::generator().mul(&random_scalar(&mut config.rng));
+        let bases = (0..msm_n)
+            .scan(start, |base, _| {
+                let current = *base;
+                *base = base.add(&G1::generator());
+
+                Some(current)
+            })
+            .collect();
+        let scalars = vec![[0; 4]; msm_n];
+
+        let timeout = Timeout::new(config.duration);
+
+        Ok(Self {
+            rng: config.rng,
+            n,
+            root,
+            twiddles,
+            ntt_input,
+            ntt_data,
+            bases,
+            scalars,
+            msm_result: G1::INFINITY,
+            timeout,
+        })
+    }
+
+    fn reset_ntt_data(&mut self) {
+        for coeff in self.ntt_input.iter_mut() {
+            *coeff = Fp::random(&mut self.rng);
+        }
+        self.ntt_data.copy_from_slice(&self.ntt_input);
+    }
+
+    fn reset_scalars(&mut self) {
+        for scalar in self.scalars.iter_mut() {
+            *scalar = random_scalar(&mut self.rng);
+        }
+    }
+
+    /// Checks one randomly chosen output of every transform in the batch against
+    /// a direct evaluation of the input polynomial.
+    fn verify_ntt(&mut self) -> Result<(), Error> {
+        for (input, output) in self
+            .ntt_input
+            .chunks(self.n)
+            .zip(self.ntt_data.chunks(self.n))
+        {
+            let k = self.rng.gen_range(0..self.n);
+            if ntt::evaluate(input, self.root.pow(k as u64)) != output[k] {
+                return Err(Error::NttMismatch(k));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::*;
+
+    #[test]
+    fn test_bench() {
+        let duration = Duration::from_millis(1000);
+        let start = Instant::now();
+        let result = bench(
+            &CpuFeatures {
+                num_cores: 1,
+                ..Default::default()
+            },
+            Config {
+                duration,
+                ntt_log_n: 10,
+                msm_n: 64,
+                ..Default::default()
+            },
+        );
+        let elapsed = start.elapsed();
+
+        assert!(result.is_ok(), "expected success");
+        let result = result.unwrap();
+        assert!(result.ntt_ops > 0);
+        assert!(result.ntt_tps > 0.);
+        assert!(elapsed >= duration && elapsed <= duration + Duration::from_millis(200));
+
+        println!("{result}");
+    }
+
+    #[test]
+    fn test_bench_multithread() {
+        let duration = Duration::from_millis(1000);
+        let start = Instant::now();
+        let result = bench_multithread(
+            &CpuFeatures {
+                num_cores: 8,
+                ..Default::default()
+            },
+            Config {
+                duration,
+                ntt_log_n: 10,
+                msm_n: 64,
+                ..Default::default()
+            },
+        );
+        let elapsed = start.elapsed();
+
+        assert!(result.is_ok(), "expected success");
+        let result = result.unwrap();
+        assert!(result.ntt_ops > 0);
+        assert!(result.ntt_tps > 0.);
+        assert!(elapsed >= duration && elapsed <= duration + Duration::from_millis(200));
+
+        println!("{result}");
+    }
+
+    #[test]
+    fn test_bench_degenerate_sizes() {
+        let config = || Config {
+            duration: Duration::from_millis(100),
+            ntt_log_n: 0,
+            msm_n: 0,
+            ..Default::default()
+        };
+        let features = CpuFeatures {
+            num_cores: 4,
+            ..Default::default()
+        };
+
+        assert!(bench(&features, config()).is_ok(), "expected success");
+        assert!(
+            bench_multithread(&features, config()).is_ok(),
+            "expected success"
+        );
+    }
+
+    #[test]
+    fn test_bench_ntt_size_too_large() {
+        for ntt_log_n in [33, 64] {
+            let config = || Config {
+                duration: Duration::from_millis(100),
+                ntt_log_n,
+                ..Default::default()
+            };
+            let features = CpuFeatures {
+                num_cores: 2,
+                ..Default::default()
+            };
+
+            assert!(matches!(
+                bench(&features, config()),
+                Err(Error::NttSizeTooLarge(n)) if n == ntt_log_n
+            ));
+            assert!(matches!(
+                bench_multithread(&features, config()),
+                Err(Error::NttSizeTooLarge(n)) if n == ntt_log_n
+            ));
+        }
+    }
+
+    #[test]
+    fn test_ntt() {
+        let mut rng = rand::thread_rng();
+        let log_n = 5;
+        let n = 1 << log_n;
+        let root = Fp::root_of_unity(log_n);
+        let twiddles = (0..n / 2).map(|k| root.pow(k)).collect::<Vec<_>>();
+
+        let input = (0..n).map(|_| Fp::random(&mut rng)).collect::<Vec<_>>();
+        let mut data = input.clone();
+
+        let result = ntt::run_test(&mut data, &twiddles, None);
+
+        assert!(result.is_ok(), "expected success");
+        assert_eq!(3 * 16 * 5, result.unwrap());
+        for (k, &output) in data.iter().enumerate() {
+            assert_eq!(ntt::evaluate(&input, root.pow(k as u64)), output);
+        }
+    }
+
+    #[test]
+    fn test_msm() {
+        let mut rng = rand::thread_rng();
+        let bases = (1..=40)
+            .map(|k| G1::generator().mul(&[k, 0, 0, 0]))
+            .collect::<Vec<_>>();
+        let scalars = (0..40).map(|_| random_scalar(&mut rng)).collect::<Vec<_>>();
+
+        let mut result = G1::INFINITY;
+        let ops = msm::run_test(&bases, &scalars, &mut result, None);
+
+        assert!(ops.is_ok(), "expected success");
+        assert!(ops.unwrap() > 0);
+        assert_eq!(msm::naive(&bases, &scalars), result);
+    }
+
+    #[test]
+    fn test_msm_multithread() {
+        let mut rng = rand::thread_rng();
+        let bases = (1..=40)
+            .map(|k| G1::generator().mul(&[k, 0, 0, 0]))
+            .collect::<Vec<_>>();
+        let scalars = (0..40).map(|_| random_scalar(&mut rng)).collect::<Vec<_>>();
+
+        let threadpool = rayon::ThreadPoolBuilder::new()
+            .num_threads(2)
+            .build()
+            .unwrap();
+        let mut result = G1::INFINITY;
+        let ops = msm::run_test_multithread(&threadpool, &bases, &scalars, &mut result, None);
+
+        assert!(ops.is_ok(), "expected success");
+        assert_eq!(msm::naive(&bases, &scalars), result);
+    }
+}
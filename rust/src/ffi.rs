@@ -2,7 +2,9 @@ use std::{path::PathBuf, ptr::null, slice, str, time::Duration};
 
 use crate::{
     arm::{Auxval, AuxvalMask},
-    cpu, ram, storage, Bench,
+    cpu::{self, math::ElementType},
+    ram::{self, probe::Status},
+    storage, Bench, CpuFeatures,
 };
 
 #[repr(C)]
@@ -12,13 +14,21 @@ pub struct TypedU64 {
 }
 
 #[no_mangle]
+#[allow(clippy::too_many_arguments)]
 pub extern "C" fn new_bench(
     total_ram: u64,
     avail_storage: u64,
     hwcap: u64,
     hwcap2: u64,
+    dotprod_mask: TypedU64,
     sve_mask: TypedU64,
     i8mm_mask: TypedU64,
+    aes_mask: TypedU64,
+    pmull_mask: TypedU64,
+    sha1_mask: TypedU64,
+    sha2_mask: TypedU64,
+    sha3_mask: TypedU64,
+    sha512_mask: TypedU64,
 ) -> *mut Bench {
     let bench = Bench::with_auxval(
         total_ram,
@@ -26,8 +36,15 @@ pub extern "C" fn new_bench(
         Auxval {
             hwcap,
             hwcap2,
+            dotprod_mask: dotprod_mask.into(),
             sve_mask: sve_mask.into(),
             i8mm_mask: i8mm_mask.into(),
+            aes_mask: aes_mask.into(),
+            pmull_mask: pmull_mask.into(),
+            sha1_mask: sha1_mask.into(),
+            sha2_mask: sha2_mask.into(),
+            sha3_mask: sha3_mask.into(),
+            sha512_mask: sha512_mask.into(),
         },
     );
 
@@ -45,18 +62,63 @@ pub struct CpuConfig {
     enc_data_len: usize,
     math_data_len: usize,
     sort_data_len: usize,
+    crypto_soft_baseline: bool,
+    /// Also runs the NTT and MSM workloads.
+    zk: bool,
+    /// Also runs the 2048-bit multiplication and modular exponentiation.
+    bigint: bool,
+    /// One of the `MATH_ELEMENT_TYPE_*` values, f32 if unknown.
+    math_element_type: u8,
+    /// Also runs the quantized and f32 neural network inference.
+    inference: bool,
 }
 
 #[repr(C)]
 pub struct CpuReport {
     crypto_tps: f64,
-    math_tps: f64,
-    sort_tps: f64,
+    /// Matrix multiplication throughput in GOPS, or GFLOPS for floating point elements.
+    math_gops: f64,
+    /// Sorted elements per second.
+    sort_eps: f64,
+    /// Software-only crypto baseline, `0` unless enabled in the config.
+    crypto_soft_tps: f64,
+    /// Detected CPU features, one bit per `FEATURE_*` flag.
+    features: u64,
+    crypto_accelerated: bool,
+    /// NTT and MSM field operations per second, `0` unless enabled in the config.
+    zk_ntt_tps: f64,
+    zk_msm_tps: f64,
+    /// Multiplications and modular exponentiations per second, `0` unless enabled in the config.
+    bigint_mul_tps: f64,
+    bigint_modexp_tps: f64,
+    /// One of the `MATH_ELEMENT_TYPE_*` values.
+    math_element_type: u8,
+    /// i8 and f32 inferences per second, `0` unless enabled in the config.
+    inference_i8_tps: f64,
+    inference_f32_tps: f64,
 
     err: *const u8,
     err_len: usize,
 }
 
+pub const FEATURE_NEON: u64 = 1 << 0;
+pub const FEATURE_DOTPROD: u64 = 1 << 1;
+pub const FEATURE_SVE: u64 = 1 << 2;
+pub const FEATURE_I8MM: u64 = 1 << 3;
+pub const FEATURE_SSE41: u64 = 1 << 4;
+pub const FEATURE_AVX2: u64 = 1 << 5;
+pub const FEATURE_AES: u64 = 1 << 6;
+pub const FEATURE_PMULL: u64 = 1 << 7;
+pub const FEATURE_SHA1: u64 = 1 << 8;
+pub const FEATURE_SHA2: u64 = 1 << 9;
+pub const FEATURE_SHA3: u64 = 1 << 10;
+pub const FEATURE_SHA512: u64 = 1 << 11;
+
+pub const MATH_ELEMENT_TYPE_I8: u8 = 0;
+pub const MATH_ELEMENT_TYPE_I16: u8 = 1;
+pub const MATH_ELEMENT_TYPE_F32: u8 = 2;
+pub const MATH_ELEMENT_TYPE_F64: u8 = 3;
+
 #[no_mangle]
 pub extern "C" fn bench_cpu(bench: *mut Bench, config: CpuConfig) -> *const CpuReport {
     let bench = unsafe { &mut *bench };
@@ -88,6 +150,24 @@ pub struct RamConfig {
     alloc_data_len: usize,
     access_data_len: usize,
     iters: usize,
+    /// Also measures the load-to-use latency.
+    latency: bool,
+    /// Also sweeps the working set through the cache hierarchy.
+    sweep: bool,
+    /// Also runs the STREAM kernels.
+    stream: bool,
+    /// Repeats the concurrent access with 1, 2, 4, ... threads.
+    access_scaling: bool,
+    /// Also runs the allocator churn.
+    malloc: bool,
+    /// Also measures the page fault cost of anonymous mappings.
+    mmap: bool,
+    /// Also probes how much memory can be allocated, within the available memory.
+    probe: bool,
+    /// Also verifies the memory with the test patterns, a failure is reported as an error.
+    integrity: bool,
+    /// Also measures the cache coherency traffic between the cores.
+    coherency: bool,
 }
 
 #[repr(C)]
@@ -97,11 +177,55 @@ pub struct RamReport {
     access_seq_avg_t: f64,
     access_rand_avg_t: f64,
     access_con_avg_t: f64,
+    /// Average load-to-use latency in nanoseconds, `0` unless enabled in the config.
+    latency_avg_ns: f64,
+    /// Largest working set that fits into the last level cache, DRAM latency in nanoseconds and
+    /// peak read bandwidth in GB/s, `0` unless enabled in the config.
+    sweep_llc_size: u64,
+    sweep_dram_latency_ns: f64,
+    sweep_peak_gbs: f64,
+    /// Single and multithreaded STREAM triad bandwidth in GB/s, `0` unless enabled in the config.
+    stream_triad_gbs: f64,
+    stream_triad_multi_gbs: f64,
+    /// Concurrent access bandwidth in GB/s with one thread and with all cores, `0` unless enabled
+    /// in the config.
+    access_scaling_single_gbs: f64,
+    access_scaling_multi_gbs: f64,
+    /// Single and multithreaded allocations per second and peak RSS growth in bytes, `0` unless
+    /// enabled in the config.
+    malloc_aps: f64,
+    malloc_multi_aps: f64,
+    malloc_peak_rss_growth: u64,
+    /// First touch page faults per second and seconds per GiB touched, `0` unless enabled in the
+    /// config.
+    mmap_first_touch_fps: f64,
+    mmap_first_touch_gib_t: f64,
+    /// Largest size allocated in bytes and allocation throughput in GB/s, `0` unless enabled in
+    /// the config.
+    probe_max_len: u64,
+    probe_gbs: f64,
+    /// One of the `PROBE_STATUS_*` values.
+    probe_status: u8,
+    /// Mean write and verify bandwidth over the patterns in GB/s, `0` unless enabled in the
+    /// config.
+    integrity_gbs: f64,
+    /// Increments per second on shared and on padded cache lines and mean core-to-core latency in
+    /// nanoseconds, `0` unless enabled in the config.
+    coherency_shared_ops: f64,
+    coherency_padded_ops: f64,
+    coherency_avg_latency_ns: f64,
 
     err: *const u8,
     err_len: usize,
 }
 
+pub const PROBE_STATUS_NOT_RUN: u8 = 0;
+pub const PROBE_STATUS_LIMIT: u8 = 1;
+pub const PROBE_STATUS_MARGIN: u8 = 2;
+pub const PROBE_STATUS_REFUSED: u8 = 3;
+pub const PROBE_STATUS_KILLED: u8 = 4;
+pub const PROBE_STATUS_FAILED: u8 = 5;
+
 #[no_mangle]
 pub extern "C" fn bench_ram(bench: *mut Bench, config: RamConfig) -> *const RamReport {
     let bench = unsafe { &mut *bench };
@@ -173,6 +297,51 @@ impl From<TypedU64> for AuxvalMask {
     }
 }
 
+impl From<&CpuFeatures> for u64 {
+    fn from(value: &CpuFeatures) -> Self {
+        [
+            (FEATURE_NEON, value.neon),
+            (FEATURE_DOTPROD, value.dotprod),
+            (FEATURE_SVE, value.sve),
+            (FEATURE_I8MM, value.i8mm),
+            (FEATURE_SSE41, value.sse41),
+            (FEATURE_AVX2, value.avx2),
+            (FEATURE_AES, value.aes),
+            (FEATURE_PMULL, value.pmull),
+            (FEATURE_SHA1, value.sha1),
+            (FEATURE_SHA2, value.sha2),
+            (FEATURE_SHA3, value.sha3),
+            (FEATURE_SHA512, value.sha512),
+        ]
+        .into_iter()
+        .filter(|(_, enabled)| *enabled)
+        .fold(0, |mask, (flag, _)| mask | flag)
+    }
+}
+
+impl From<u8> for ElementType {
+    fn from(value: u8) -> Self {
+        match value {
+            MATH_ELEMENT_TYPE_I8 => Self::I8,
+            MATH_ELEMENT_TYPE_I16 => Self::I16,
+            MATH_ELEMENT_TYPE_F32 => Self::F32,
+            MATH_ELEMENT_TYPE_F64 => Self::F64,
+            _ => Self::F32,
+        }
+    }
+}
+
+impl From<ElementType> for u8 {
+    fn from(value: ElementType) -> Self {
+        match value {
+            ElementType::I8 => MATH_ELEMENT_TYPE_I8,
+            ElementType::I16 => MATH_ELEMENT_TYPE_I16,
+            ElementType::F32 => MATH_ELEMENT_TYPE_F32,
+            ElementType::F64 => MATH_ELEMENT_TYPE_F64,
+        }
+    }
+}
+
 impl From<CpuConfig> for cpu::Config {
     fn from(value: CpuConfig) -> Self {
         let duration = Duration::from_millis(value.duration as u64) / 3;
@@ -181,11 +350,13 @@ impl From<CpuConfig> for cpu::Config {
             crypto: cpu::crypto::Config {
                 duration,
                 data_len: value.enc_data_len.try_into().unwrap(),
+                soft_baseline: value.crypto_soft_baseline,
                 ..Default::default()
             },
             math: cpu::math::Config {
                 duration,
                 n: value.math_data_len.try_into().unwrap(),
+                element_type: value.math_element_type.into(),
                 ..Default::default()
             },
             sort: cpu::sort::Config {
@@ -193,6 +364,18 @@ impl From<CpuConfig> for cpu::Config {
                 data_len: value.sort_data_len.try_into().unwrap(),
                 ..Default::default()
             },
+            zk: value.zk.then(|| cpu::zk::Config {
+                duration,
+                ..Default::default()
+            }),
+            bigint: value.bigint.then(|| cpu::bigint::Config {
+                duration,
+                ..Default::default()
+            }),
+            inference: value.inference.then(|| cpu::inference::Config {
+                duration,
+                ..Default::default()
+            }),
         }
     }
 }
@@ -202,8 +385,27 @@ impl From<Result<cpu::Report, cpu::Error>> for CpuReport {
         match value {
             Ok(report) => Self {
                 crypto_tps: report.crypto.tps,
-                math_tps: report.math.tps,
-                sort_tps: report.sort.tps,
+                math_gops: report.math.gops,
+                sort_eps: report.sort.eps,
+                crypto_soft_tps: report.crypto.soft_tps.unwrap_or_default(),
+                features: (&report.features).into(),
+                crypto_accelerated: report.crypto.accelerated,
+                zk_ntt_tps: report.zk.as_ref().map_or(0., |zk| zk.ntt_tps),
+                zk_msm_tps: report.zk.as_ref().map_or(0., |zk| zk.msm_tps),
+                bigint_mul_tps: report.bigint.as_ref().map_or(0., |bigint| bigint.mul_tps),
+                bigint_modexp_tps: report
+                    .bigint
+                    .as_ref()
+                    .map_or(0., |bigint| bigint.modexp_tps),
+                math_element_type: report.math.element_type.into(),
+                inference_i8_tps: report
+                    .inference
+                    .as_ref()
+                    .map_or(0., |inference| inference.i8_tps),
+                inference_f32_tps: report
+                    .inference
+                    .as_ref()
+                    .map_or(0., |inference| inference.f32_tps),
                 err: null(),
                 err_len: 0,
             },
@@ -211,8 +413,18 @@ impl From<Result<cpu::Report, cpu::Error>> for CpuReport {
                 let err = format!("{err:?}");
                 let report = Self {
                     crypto_tps: 0.,
-                    math_tps: 0.,
-                    sort_tps: 0.,
+                    math_gops: 0.,
+                    sort_eps: 0.,
+                    crypto_soft_tps: 0.,
+                    features: 0,
+                    crypto_accelerated: false,
+                    zk_ntt_tps: 0.,
+                    zk_msm_tps: 0.,
+                    bigint_mul_tps: 0.,
+                    bigint_modexp_tps: 0.,
+                    math_element_type: MATH_ELEMENT_TYPE_F32,
+                    inference_i8_tps: 0.,
+                    inference_f32_tps: 0.,
                     err: err.as_ptr(),
                     err_len: err.len(),
                 };
@@ -225,6 +437,18 @@ impl From<Result<cpu::Report, cpu::Error>> for CpuReport {
     }
 }
 
+impl From<Status> for u8 {
+    fn from(value: Status) -> Self {
+        match value {
+            Status::Limit => PROBE_STATUS_LIMIT,
+            Status::Margin => PROBE_STATUS_MARGIN,
+            Status::Refused => PROBE_STATUS_REFUSED,
+            Status::Killed(_) => PROBE_STATUS_KILLED,
+            Status::Failed(_) => PROBE_STATUS_FAILED,
+        }
+    }
+}
+
 impl From<RamConfig> for ram::Config {
     fn from(value: RamConfig) -> Self {
         Self {
@@ -236,8 +460,23 @@ impl From<RamConfig> for ram::Config {
             access: ram::access::Config {
                 data_len: value.access_data_len,
                 iters: value.iters,
+                scaling: value.access_scaling,
                 ..Default::default()
             },
+            latency: value.latency.then(|| ram::latency::Config {
+                iters: value.iters,
+                ..Default::default()
+            }),
+            sweep: value.sweep.then(Default::default),
+            stream: value.stream.then(|| ram::stream::Config {
+                iters: value.iters,
+                ..Default::default()
+            }),
+            malloc: value.malloc.then(Default::default),
+            mmap: value.mmap.then(Default::default),
+            probe: value.probe.then(Default::default),
+            integrity: value.integrity.then(Default::default),
+            coherency: value.coherency.then(Default::default),
         }
     }
 }
@@ -251,6 +490,89 @@ impl From<Result<ram::Report, ram::Error>> for RamReport {
                 access_seq_avg_t: report.access.seq_avg_t.as_secs_f64(),
                 access_rand_avg_t: report.access.rand_avg_t.as_secs_f64(),
                 access_con_avg_t: report.access.con_avg_t.as_secs_f64(),
+                latency_avg_ns: report.latency.as_ref().map_or(0., |latency| latency.avg_ns),
+                sweep_llc_size: report
+                    .sweep
+                    .as_ref()
+                    .and_then(|sweep| sweep.levels.iter().filter_map(|level| level.size).max())
+                    .map_or(0, |size| size as u64),
+                sweep_dram_latency_ns: report
+                    .sweep
+                    .as_ref()
+                    .and_then(|sweep| sweep.levels.last())
+                    .map_or(0., |level| level.latency_ns),
+                sweep_peak_gbs: report.sweep.as_ref().map_or(0., |sweep| {
+                    sweep
+                        .points
+                        .iter()
+                        .map(|point| point.bandwidth_gbs)
+                        .fold(0., f64::max)
+                }),
+                stream_triad_gbs: report
+                    .stream
+                    .as_ref()
+                    .map_or(0., |stream| triad_gbs(&stream.single)),
+                stream_triad_multi_gbs: report
+                    .stream
+                    .as_ref()
+                    .map_or(0., |stream| triad_gbs(&stream.multi)),
+                access_scaling_single_gbs: report
+                    .access
+                    .scaling
+                    .first()
+                    .map_or(0., |scaling| scaling.gbs),
+                access_scaling_multi_gbs: report
+                    .access
+                    .scaling
+                    .last()
+                    .map_or(0., |scaling| scaling.gbs),
+                malloc_aps: report
+                    .malloc
+                    .as_ref()
+                    .map_or(0., |malloc| malloc.single.aps),
+                malloc_multi_aps: report.malloc.as_ref().map_or(0., |malloc| malloc.multi.aps),
+                malloc_peak_rss_growth: report
+                    .malloc
+                    .as_ref()
+                    .map_or(0, |malloc| malloc.peak_rss_growth),
+                mmap_first_touch_fps: report.mmap.as_ref().map_or(0., |mmap| mmap.first_touch.fps),
+                mmap_first_touch_gib_t: report
+                    .mmap
+                    .as_ref()
+                    .map_or(0., |mmap| mmap.first_touch.gib_t.as_secs_f64()),
+                probe_max_len: report
+                    .probe
+                    .as_ref()
+                    .map_or(0, |probe| probe.max_len as u64),
+                probe_gbs: report.probe.as_ref().map_or(0., |probe| probe.gbs),
+                probe_status: report
+                    .probe
+                    .as_ref()
+                    .map_or(PROBE_STATUS_NOT_RUN, |probe| probe.status.into()),
+                integrity_gbs: report
+                    .integrity
+                    .as_ref()
+                    .filter(|integrity| !integrity.patterns.is_empty())
+                    .map_or(0., |integrity| {
+                        integrity
+                            .patterns
+                            .iter()
+                            .map(|pattern| pattern.gbs)
+                            .sum::<f64>()
+                            / integrity.patterns.len() as f64
+                    }),
+                coherency_shared_ops: report
+                    .coherency
+                    .as_ref()
+                    .map_or(0., |coherency| coherency.shared_ops),
+                coherency_padded_ops: report
+                    .coherency
+                    .as_ref()
+                    .map_or(0., |coherency| coherency.padded_ops),
+                coherency_avg_latency_ns: report
+                    .coherency
+                    .as_ref()
+                    .map_or(0., |coherency| avg_latency_ns(&coherency.latencies)),
                 err: null(),
                 err_len: 0,
             },
@@ -262,6 +584,26 @@ impl From<Result<ram::Report, ram::Error>> for RamReport {
                     access_seq_avg_t: 0.,
                     access_rand_avg_t: 0.,
                     access_con_avg_t: 0.,
+                    latency_avg_ns: 0.,
+                    sweep_llc_size: 0,
+                    sweep_dram_latency_ns: 0.,
+                    sweep_peak_gbs: 0.,
+                    stream_triad_gbs: 0.,
+                    stream_triad_multi_gbs: 0.,
+                    access_scaling_single_gbs: 0.,
+                    access_scaling_multi_gbs: 0.,
+                    malloc_aps: 0.,
+                    malloc_multi_aps: 0.,
+                    malloc_peak_rss_growth: 0,
+                    mmap_first_touch_fps: 0.,
+                    mmap_first_touch_gib_t: 0.,
+                    probe_max_len: 0,
+                    probe_gbs: 0.,
+                    probe_status: PROBE_STATUS_NOT_RUN,
+                    integrity_gbs: 0.,
+                    coherency_shared_ops: 0.,
+                    coherency_padded_ops: 0.,
+                    coherency_avg_latency_ns: 0.,
                     err: err.as_ptr(),
                     err_len: err.len(),
                 };
@@ -274,6 +616,34 @@ impl From<Result<ram::Report, ram::Error>> for RamReport {
     }
 }
 
+fn triad_gbs(bandwidths: &[ram::stream::Bandwidth]) -> f64 {
+    bandwidths
+        .iter()
+        .find(|bandwidth| bandwidth.kernel == ram::stream::Kernel::Triad)
+        .map_or(0., |bandwidth| bandwidth.gbs)
+}
+
+/// Mean of the core-to-core latencies, leaving out the diagonal.
+fn avg_latency_ns(latencies: &[Vec<f64>]) -> f64 {
+    let (sum, count) = latencies
+        .iter()
+        .enumerate()
+        .flat_map(|(a, row)| {
+            row.iter()
+                .enumerate()
+                .filter(move |(b, _)| a != *b)
+                .map(|(_, latency_ns)| *latency_ns)
+        })
+        .fold((0., 0), |(sum, count), latency_ns| {
+            (sum + latency_ns, count + 1)
+        });
+
+    match count {
+        0 => 0.,
+        _ => sum / count as f64,
+    }
+}
+
 impl From<StorageConfig> for storage::Config {
     fn from(value: StorageConfig) -> Self {
         let dir =
@@ -1,5 +1,7 @@
 #[cfg(any(target_arch = "arm", target_arch = "aarch64"))]
 pub mod arm;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub mod x86;
 
 #[cfg(any(target_os = "android", target_os = "ios"))]
 pub mod ffi;
@@ -10,11 +12,55 @@ mod storage;
 
 mod utils;
 
-pub(crate) struct CpuFeatures {
-    num_cores: usize,
+use std::fmt;
 
-    sve: bool,
-    i8mm: bool,
+#[derive(Debug, Clone, Default)]
+pub struct CpuFeatures {
+    pub(crate) num_cores: usize,
+
+    pub(crate) neon: bool,
+    pub(crate) dotprod: bool,
+    pub(crate) sve: bool,
+    pub(crate) i8mm: bool,
+    pub(crate) sse41: bool,
+    pub(crate) avx2: bool,
+
+    pub(crate) aes: bool,
+    pub(crate) pmull: bool,
+    pub(crate) sha1: bool,
+    pub(crate) sha2: bool,
+    pub(crate) sha3: bool,
+    pub(crate) sha512: bool,
+}
+
+impl fmt::Display for CpuFeatures {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let flags = [
+            ("neon", self.neon),
+            ("dotprod", self.dotprod),
+            ("sve", self.sve),
+            ("i8mm", self.i8mm),
+            ("sse4.1", self.sse41),
+            ("avx2", self.avx2),
+            ("aes", self.aes),
+            ("pmull", self.pmull),
+            ("sha1", self.sha1),
+            ("sha2", self.sha2),
+            ("sha3", self.sha3),
+            ("sha512", self.sha512),
+        ]
+        .into_iter()
+        .filter(|(_, supported)| *supported)
+        .map(|(name, _)| name)
+        .collect::<Vec<_>>();
+
+        write!(f, "features ... {} cores", self.num_cores)?;
+        if !flags.is_empty() {
+            write!(f, ", {}", flags.join(" "))?;
+        }
+
+        Ok(())
+    }
 }
 
 pub struct Bench {
@@ -39,11 +85,30 @@ impl Bench {
             cpu::math::bench(&self.features, config.math).map_err(|err| cpu::Error::Math(err))?;
         let sort_report =
             cpu::sort::bench(&self.features, config.sort).map_err(|err| cpu::Error::Sort(err))?;
+        let zk_report = config
+            .zk
+            .map(|config| cpu::zk::bench(&self.features, config))
+            .transpose()
+            .map_err(cpu::Error::Zk)?;
+        let bigint_report = config
+            .bigint
+            .map(|config| cpu::bigint::bench(&self.features, config))
+            .transpose()
+            .map_err(cpu::Error::Bigint)?;
+        let inference_report = config
+            .inference
+            .map(|config| cpu::inference::bench(&self.features, config))
+            .transpose()
+            .map_err(cpu::Error::Inference)?;
 
         Ok(cpu::Report {
+            features: self.features.clone(),
             crypto: crypto_report,
             math: math_report,
             sort: sort_report,
+            zk: zk_report,
+            bigint: bigint_report,
+            inference: inference_report,
         })
     }
 
@@ -54,11 +119,30 @@ impl Bench {
             .map_err(|err| cpu::Error::Math(err))?;
         let sort_report = cpu::sort::bench_multithread(&self.features, config.sort)
             .map_err(|err| cpu::Error::Sort(err))?;
+        let zk_report = config
+            .zk
+            .map(|config| cpu::zk::bench_multithread(&self.features, config))
+            .transpose()
+            .map_err(cpu::Error::Zk)?;
+        let bigint_report = config
+            .bigint
+            .map(|config| cpu::bigint::bench_multithread(&self.features, config))
+            .transpose()
+            .map_err(cpu::Error::Bigint)?;
+        let inference_report = config
+            .inference
+            .map(|config| cpu::inference::bench_multithread(&self.features, config))
+            .transpose()
+            .map_err(cpu::Error::Inference)?;
 
         Ok(cpu::Report {
+            features: self.features.clone(),
             crypto: crypto_report,
             math: math_report,
             sort: sort_report,
+            zk: zk_report,
+            bigint: bigint_report,
+            inference: inference_report,
         })
     }
 
@@ -66,11 +150,59 @@ impl Bench {
         let alloc_report = ram::alloc::bench(config.alloc).map_err(|err| ram::Error::Alloc(err))?;
         let access_report = ram::access::bench(&self.features, config.access)
             .map_err(|err| ram::Error::Access(err))?;
+        let latency_report = config
+            .latency
+            .map(ram::latency::bench)
+            .transpose()
+            .map_err(ram::Error::Latency)?;
+        let sweep_report = config
+            .sweep
+            .map(|config| ram::sweep::bench(config, self.total_ram))
+            .transpose()
+            .map_err(ram::Error::Sweep)?;
+        let stream_report = config
+            .stream
+            .map(|config| ram::stream::bench(&self.features, config))
+            .transpose()
+            .map_err(ram::Error::Stream)?;
+        let malloc_report = config
+            .malloc
+            .map(|config| ram::malloc::bench(&self.features, config))
+            .transpose()
+            .map_err(ram::Error::Malloc)?;
+        let mmap_report = config
+            .mmap
+            .map(ram::mmap::bench)
+            .transpose()
+            .map_err(ram::Error::Mmap)?;
+        let probe_report = config
+            .probe
+            .map(|config| ram::probe::bench(config, self.total_ram))
+            .transpose()
+            .map_err(ram::Error::Probe)?;
+        let integrity_report = config
+            .integrity
+            .map(ram::integrity::bench)
+            .transpose()
+            .map_err(ram::Error::Integrity)?;
+        let coherency_report = config
+            .coherency
+            .map(|config| ram::coherency::bench(&self.features, config))
+            .transpose()
+            .map_err(ram::Error::Coherency)?;
 
         Ok(ram::Report {
             total_mem: self.total_ram,
             alloc: alloc_report,
             access: access_report,
+            latency: latency_report,
+            sweep: sweep_report,
+            stream: stream_report,
+            malloc: malloc_report,
+            mmap: mmap_report,
+            probe: probe_report,
+            integrity: integrity_report,
+            coherency: coherency_report,
         })
     }
 
@@ -100,8 +232,7 @@ mod tests {
             0,
             CpuFeatures {
                 num_cores: 8,
-                sve: false,
-                i8mm: false,
+                ..Default::default()
             },
         );
         let duration = Duration::from_secs(1);
@@ -122,13 +253,16 @@ mod tests {
                 data_len: 100_000,
                 ..Default::default()
             },
+            zk: None,
+            bigint: None,
+            inference: None,
         });
 
         assert!(result.is_ok(), "expected success");
         let result = result.unwrap();
         assert!(result.crypto.tps > 0.);
         assert!(result.math.tps > 0.);
-        assert!(result.sort.tps > 0.);
+        assert!(result.sort.eps > 0.);
 
         println!("{result}");
     }
@@ -140,8 +274,7 @@ mod tests {
             0,
             CpuFeatures {
                 num_cores: 8,
-                sve: false,
-                i8mm: false,
+                ..Default::default()
             },
         );
         let duration = Duration::from_secs(1);
@@ -162,13 +295,16 @@ mod tests {
                 data_len: 100_000,
                 ..Default::default()
             },
+            zk: None,
+            bigint: None,
+            inference: None,
         });
 
         assert!(result.is_ok(), "expected success");
         let result = result.unwrap();
         assert!(result.crypto.tps > 0.);
         assert!(result.math.tps > 0.);
-        assert!(result.sort.tps > 0.);
+        assert!(result.sort.eps > 0.);
 
         println!("{result}");
     }
@@ -180,8 +316,7 @@ mod tests {
             0,
             CpuFeatures {
                 num_cores: 8,
-                sve: false,
-                i8mm: false,
+                ..Default::default()
             },
         );
         let result = bench.ram(ram::Config {
@@ -195,6 +330,14 @@ mod tests {
                 iters: 5,
                 ..Default::default()
             },
+            latency: None,
+            sweep: None,
+            stream: None,
+            malloc: None,
+            mmap: None,
+            probe: None,
+            integrity: None,
+            coherency: None,
         });
 
         assert!(result.is_ok(), "expected success");
@@ -211,8 +354,7 @@ mod tests {
             72 * GB as u64,
             CpuFeatures {
                 num_cores: 8,
-                sve: false,
-                i8mm: false,
+                ..Default::default()
             },
         );
         let result = bench.storage(storage::Config {
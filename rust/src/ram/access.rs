@@ -4,9 +4,7 @@ use std::{
     time::{Duration, Instant},
 };
 
-use rand::seq::SliceRandom;
-
-use crate::{utils::{Avg, KB}, CpuFeatures};
+use crate::{utils::{Avg, Size, KB, MB}, CpuFeatures};
 
 pub(crate) fn bench(features: &CpuFeatures, config: Config) -> Result<Report, Error> {
     let mut context = Context::new(config);
@@ -21,38 +19,69 @@ pub(crate) fn bench(features: &CpuFeatures, config: Config) -> Result<Report, Er
         report_builder.add_seq(start.elapsed());
     }
 
-    let indices = (0..context.data.len()).collect::<Vec<_>>();
-    let mut write_indices;
-    let mut read_indices;
+    let mut write_seed;
+    let mut read_seed;
     for _ in 0..context.iters {
         context.reset_data();
 
-        write_indices = indices.clone();
-        write_indices.shuffle(&mut context.rng);
-
-        read_indices = indices.clone();
-        read_indices.shuffle(&mut context.rng);
+        write_seed = context.rng.next_u64();
+        read_seed = context.rng.next_u64();
 
         start = Instant::now();
-        black_box(random::run_test(
-            &mut context.data,
-            &write_indices,
-            &read_indices,
-        )?);
+        black_box(random::run_test(&mut context.data, write_seed, read_seed)?);
         report_builder.add_rand(start.elapsed());
     }
 
-    let chunk_size = context.data.len().div_ceil(features.num_cores);
+    let threadpool = new_threadpool(features.num_cores);
     for _ in 0..context.iters {
-        context.reset_data();
-        let chunks = context.data.chunks_mut(chunk_size).collect::<Vec<_>>();
+        report_builder.add_con(run_concurrent(&mut context.data, &threadpool)?);
+    }
 
-        start = Instant::now();
-        black_box(concurrent::run_test(chunks)?);
-        report_builder.add_con(start.elapsed());
+    if context.scaling {
+        for num_threads in thread_counts(features.num_cores) {
+            let threadpool = new_threadpool(num_threads);
+
+            let mut ts = Vec::with_capacity(context.iters);
+            for _ in 0..context.iters {
+                ts.push(run_concurrent(&mut context.scaling_data, &threadpool)?);
+            }
+            report_builder.add_scaling(num_threads, ts);
+        }
     }
 
-    Ok(report_builder.build())
+    Ok(report_builder.build(context.scaling_data.len()))
+}
+
+fn new_threadpool(num_threads: usize) -> rayon::ThreadPool {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .build()
+        .unwrap()
+}
+
+/// Powers of two below `num_cores`, followed by `num_cores`.
+fn thread_counts(num_cores: usize) -> Vec<usize> {
+    let num_cores = num_cores.max(1);
+
+    let mut thread_counts = (0..usize::BITS)
+        .map(|i| 1 << i)
+        .take_while(|&n| n < num_cores)
+        .collect::<Vec<_>>();
+    thread_counts.push(num_cores);
+
+    thread_counts
+}
+
+/// Runs the concurrent test with `data` split evenly between the threads of `threadpool`.
+fn run_concurrent(data: &mut Vec<u8>, threadpool: &rayon::ThreadPool) -> Result<Duration, Error> {
+    reset(data);
+    let chunk_size = data.len().div_ceil(threadpool.current_num_threads()).max(1);
+    let chunks = data.chunks_mut(chunk_size).collect::<Vec<_>>();
+
+    let start = Instant::now();
+    black_box(threadpool.install(|| concurrent::run_test(chunks))?);
+
+    Ok(start.elapsed())
 }
 
 mod sequential {
@@ -77,15 +106,11 @@ mod sequential {
 mod random {
     use super::*;
 
-    pub(super) fn run_test(
-        data: &mut [u8],
-        write_indices: &Vec<usize>,
-        read_indices: &Vec<usize>,
-    ) -> Result<(), Error> {
-        for &i in write_indices {
+    pub(super) fn run_test(data: &mut [u8], write_seed: u64, read_seed: u64) -> Result<(), Error> {
+        for i in Permutation::new(data.len(), write_seed) {
             data[i] = (i % 256) as u8;
         }
-        for &i in read_indices {
+        for i in Permutation::new(data.len(), read_seed) {
             let v = data[i];
             let expected = (i % 256) as u8;
             if v != expected {
@@ -95,6 +120,60 @@ mod random {
 
         Ok(())
     }
+
+    const SCRAMBLER: u64 = 0x9e37_79b9_7f4a_7c15;
+
+    /// Visits every index of `0..len` once in random order, generated in registers:
+    /// a Weyl sequence modulo the next power of two, scrambled by a xorshift-multiply bijection,
+    /// skipping the values out of range.
+    pub(super) struct Permutation {
+        state: u64,
+        increment: u64,
+        mask: u64,
+        shift: u32,
+        len: u64,
+        remaining: usize,
+    }
+
+    impl Permutation {
+        pub(super) fn new(len: usize, seed: u64) -> Self {
+            let bits = len.next_power_of_two().trailing_zeros();
+
+            Self {
+                state: seed,
+                // odd, so that the sequence has a full period
+                increment: (seed >> 32) | 1,
+                mask: (1u64 << bits) - 1,
+                shift: (bits / 2).max(1),
+                len: len as u64,
+                remaining: len,
+            }
+        }
+    }
+
+    impl Iterator for Permutation {
+        type Item = usize;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            if self.remaining == 0 {
+                return None;
+            }
+
+            loop {
+                self.state = self.state.wrapping_add(self.increment) & self.mask;
+
+                let mut x = self.state;
+                x ^= x >> self.shift;
+                x = x.wrapping_mul(SCRAMBLER) & self.mask;
+                x ^= x >> self.shift;
+
+                if x < self.len {
+                    self.remaining -= 1;
+                    return Some(x as usize);
+                }
+            }
+        }
+    }
 }
 
 mod concurrent {
@@ -136,6 +215,10 @@ pub struct Config {
     pub rng: Box<dyn rand::RngCore>,
     pub data_len: usize,
     pub iters: usize,
+    /// Repeats the concurrent test with 1, 2, 4, ... up to `num_cores` threads.
+    pub scaling: bool,
+    /// Working set of the scaling runs, well above the last level cache to measure DRAM bandwidth.
+    pub scaling_data_len: usize,
 }
 
 impl Default for Config {
@@ -144,6 +227,8 @@ impl Default for Config {
             rng: Box::new(rand::thread_rng()),
             data_len: 64 * KB,
             iters: 100,
+            scaling: false,
+            scaling_data_len: 256 * MB,
         }
     }
 }
@@ -157,6 +242,16 @@ pub struct Report {
     pub seq_avg_t: Duration,
     pub rand_avg_t: Duration,
     pub con_avg_t: Duration,
+    /// Concurrent access per thread count, empty unless scaling is enabled.
+    pub scaling: Vec<ScalingReport>,
+    pub scaling_data_len: usize,
+}
+
+pub struct ScalingReport {
+    pub num_threads: usize,
+    pub avg_t: Duration,
+    /// Bandwidth of the writes and reads in GB/s.
+    pub gbs: f64,
 }
 
 impl fmt::Display for Report {
@@ -176,6 +271,16 @@ impl fmt::Display for Report {
             "concurrent access ... {:.6} s",
             self.con_avg_t.as_secs_f64()
         )?;
+        for scaling in self.scaling.iter() {
+            write!(
+                f,
+                "\nconcurrent access ({} threads, {}) ... {:.6} s, {:.2} GB/s",
+                scaling.num_threads,
+                Size(self.scaling_data_len),
+                scaling.avg_t.as_secs_f64(),
+                scaling.gbs
+            )?;
+        }
 
         Ok(())
     }
@@ -185,6 +290,7 @@ struct ReportBuilder {
     seq_ts: Vec<Duration>,
     rand_ts: Vec<Duration>,
     con_ts: Vec<Duration>,
+    scaling_ts: Vec<(usize, Vec<Duration>)>,
 }
 
 impl ReportBuilder {
@@ -193,6 +299,7 @@ impl ReportBuilder {
             seq_ts: Vec::with_capacity(iters),
             rand_ts: Vec::with_capacity(iters),
             con_ts: Vec::with_capacity(iters),
+            scaling_ts: Vec::new(),
         }
     }
 
@@ -208,11 +315,33 @@ impl ReportBuilder {
         self.con_ts.push(time);
     }
 
-    fn build(self) -> Report {
+    fn add_scaling(&mut self, num_threads: usize, ts: Vec<Duration>) {
+        self.scaling_ts.push((num_threads, ts));
+    }
+
+    fn build(self, scaling_data_len: usize) -> Report {
         Report {
             seq_avg_t: self.seq_ts.avg(),
             rand_avg_t: self.rand_ts.avg(),
             con_avg_t: self.con_ts.avg(),
+            scaling: self
+                .scaling_ts
+                .into_iter()
+                .map(|(num_threads, ts)| {
+                    let avg_t = ts.avg();
+
+                    ScalingReport {
+                        num_threads,
+                        avg_t,
+                        // every byte is written and read once
+                        gbs: match avg_t {
+                            Duration::ZERO => 0.,
+                            _ => (2 * scaling_data_len) as f64 / avg_t.as_secs_f64() / 1e9,
+                        },
+                    }
+                })
+                .collect(),
+            scaling_data_len,
         }
     }
 }
@@ -221,27 +350,40 @@ struct Context {
     rng: Box<dyn rand::RngCore>,
 
     iters: usize,
+    scaling: bool,
     data: Vec<u8>,
+    /// Allocated only when scaling is enabled.
+    scaling_data: Vec<u8>,
 }
 
 impl Context {
     fn new(config: Config) -> Self {
         let data = vec![0u8; config.data_len];
+        let scaling_data = match config.scaling {
+            true => vec![0u8; config.scaling_data_len],
+            false => Vec::new(),
+        };
 
         Self {
             rng: config.rng,
             iters: config.iters,
+            scaling: config.scaling,
             data,
+            scaling_data,
         }
     }
 
     fn reset_data(&mut self) {
-        let size = self.data.len();
-        self.data.clear();
-        self.data.resize(size, 0);
+        reset(&mut self.data);
     }
 }
 
+fn reset(data: &mut Vec<u8>) {
+    let size = data.len();
+    data.clear();
+    data.resize(size, 0);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -251,8 +393,7 @@ mod tests {
         let result = bench(
             &CpuFeatures {
                 num_cores: 8,
-                sve: false,
-                i8mm: false,
+                ..Default::default()
             },
             Config {
                 data_len: 64,
@@ -265,7 +406,61 @@ mod tests {
         let result = result.unwrap();
         assert!(result.seq_avg_t > Duration::ZERO);
         assert!(result.rand_avg_t > Duration::ZERO);
+        assert!(result.scaling.is_empty());
 
         println!("{result}");
     }
+
+    #[test]
+    fn test_bench_scaling() {
+        let result = bench(
+            &CpuFeatures {
+                num_cores: 6,
+                ..Default::default()
+            },
+            Config {
+                data_len: 64 * KB,
+                iters: 5,
+                scaling: true,
+                scaling_data_len: 4 * MB,
+                ..Default::default()
+            },
+        );
+
+        assert!(result.is_ok(), "expected success");
+        let result = result.unwrap();
+        assert_eq!(
+            vec![1, 2, 4, 6],
+            result
+                .scaling
+                .iter()
+                .map(|scaling| scaling.num_threads)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(4 * MB, result.scaling_data_len);
+        assert!(result.scaling.iter().all(|scaling| scaling.gbs > 0.));
+
+        println!("{result}");
+    }
+
+    #[test]
+    fn test_permutation() {
+        for len in [1, 7, 64, 1000] {
+            for seed in [0, 42, u64::MAX] {
+                let mut indices = random::Permutation::new(len, seed).collect::<Vec<_>>();
+                assert_eq!(len, indices.len());
+
+                indices.sort();
+                assert_eq!((0..len).collect::<Vec<_>>(), indices);
+            }
+        }
+    }
+
+    #[test]
+    fn test_thread_counts() {
+        assert_eq!(vec![1], thread_counts(1));
+        assert_eq!(vec![1, 2], thread_counts(2));
+        assert_eq!(vec![1, 2, 4, 8], thread_counts(8));
+        assert_eq!(vec![1, 2, 4, 8, 12], thread_counts(12));
+    }
 }
@@ -0,0 +1,289 @@
+use std::{
+    fmt,
+    hint::spin_loop,
+    sync::atomic::{AtomicU64, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::CpuFeatures;
+
+pub(crate) fn bench(features: &CpuFeatures, config: Config) -> Result<Report, Error> {
+    let context = Context::new(features, config);
+
+    let shared = (0..context.num_threads)
+        .map(|_| AtomicU64::new(0))
+        .collect::<Vec<_>>();
+    let shared_t = run_counters(&shared, context.ops)?;
+
+    let padded = (0..context.num_threads)
+        .map(|_| Padded(AtomicU64::new(0)))
+        .collect::<Vec<_>>();
+    let padded_t = run_counters(&padded, context.ops)?;
+
+    let mut latencies = vec![vec![0.; context.num_threads]; context.num_threads];
+    let mut pinned = true;
+    for a in 0..context.num_threads {
+        for b in a + 1..context.num_threads {
+            let (duration, both_pinned) = run_ping_pong(a, b, context.rounds);
+            let latency_ns = duration.as_nanos() as f64 / (2 * context.rounds) as f64;
+
+            latencies[a][b] = latency_ns;
+            latencies[b][a] = latency_ns;
+            pinned &= both_pinned;
+        }
+    }
+
+    let ops = (context.num_threads * context.ops) as f64;
+    Ok(Report {
+        num_threads: context.num_threads,
+        shared_ops: ops_per_sec(ops, shared_t),
+        padded_ops: ops_per_sec(ops, padded_t),
+        latencies,
+        pinned,
+    })
+}
+
+/// Increments one counter per thread `ops` times, returns the time until all threads are done.
+fn run_counters<C: Counter>(counters: &[C], ops: usize) -> Result<Duration, Error> {
+    let start = Instant::now();
+    thread::scope(|scope| {
+        for counter in counters {
+            scope.spawn(move || {
+                for _ in 0..ops {
+                    counter.get().fetch_add(1, Ordering::Relaxed);
+                }
+            });
+        }
+    });
+    let elapsed = start.elapsed();
+
+    for (i, counter) in counters.iter().enumerate() {
+        let count = counter.get().load(Ordering::Relaxed);
+        if count != ops as u64 {
+            return Err(Error::InvalidCount(i, count));
+        }
+    }
+
+    Ok(elapsed)
+}
+
+/// Bounces a value between threads pinned to cores `a` and `b` for `rounds` round trips,
+/// returns the elapsed time and whether both threads could be pinned.
+fn run_ping_pong(a: usize, b: usize, rounds: usize) -> (Duration, bool) {
+    let flag = Padded(AtomicU64::new(0));
+    let rounds = rounds as u64;
+
+    thread::scope(|scope| {
+        let pong = scope.spawn(|| {
+            let pinned = pin(b);
+            for round in 0..rounds {
+                wait_for(&flag.0, 2 * round + 1);
+                flag.0.store(2 * round + 2, Ordering::Release);
+            }
+
+            pinned
+        });
+
+        // pinned in a thread of its own to leave the affinity of the caller untouched
+        let ping = scope.spawn(|| {
+            let pinned = pin(a);
+            let start = Instant::now();
+            for round in 0..rounds {
+                wait_for(&flag.0, 2 * round);
+                flag.0.store(2 * round + 1, Ordering::Release);
+            }
+            wait_for(&flag.0, 2 * rounds);
+
+            (start.elapsed(), pinned)
+        });
+
+        let (elapsed, ping_pinned) = ping.join().unwrap_or((Duration::ZERO, false));
+        (elapsed, ping_pinned && pong.join().unwrap_or(false))
+    })
+}
+
+/// Spins until `flag` holds `value`, yielding now and then in case both threads share a core.
+fn wait_for(flag: &AtomicU64, value: u64) {
+    let mut spins = 0u32;
+    while flag.load(Ordering::Acquire) != value {
+        spins = spins.wrapping_add(1);
+        match spins % SPINS_PER_YIELD {
+            0 => thread::yield_now(),
+            _ => spin_loop(),
+        }
+    }
+}
+
+const SPINS_PER_YIELD: u32 = 1 << 12;
+
+/// Pins the calling thread to `core`, returns `false` if not supported or not allowed.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn pin(core: usize) -> bool {
+    unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        libc::CPU_SET(core, &mut set);
+
+        libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set) == 0
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn pin(_core: usize) -> bool {
+    false
+}
+
+fn ops_per_sec(ops: f64, duration: Duration) -> f64 {
+    match duration {
+        Duration::ZERO => 0.,
+        _ => ops / duration.as_secs_f64(),
+    }
+}
+
+/// Keeps the value on its own cache line, 128 bytes to also cover adjacent line prefetching.
+#[repr(align(128))]
+struct Padded<T>(T);
+
+trait Counter: Sync {
+    fn get(&self) -> &AtomicU64;
+}
+
+impl Counter for AtomicU64 {
+    fn get(&self) -> &AtomicU64 {
+        self
+    }
+}
+
+impl Counter for Padded<AtomicU64> {
+    fn get(&self) -> &AtomicU64 {
+        &self.0
+    }
+}
+
+pub struct Config {
+    /// Increments per thread and counter layout.
+    pub ops: usize,
+    /// Round trips per core pair.
+    pub rounds: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            ops: 1 << 20,
+            rounds: 10_000,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    /// Thread and final value of a counter that lost increments.
+    InvalidCount(usize, u64),
+}
+
+pub struct Report {
+    pub num_threads: usize,
+    /// Increments per second with the counters packed into the same cache lines.
+    pub shared_ops: f64,
+    /// Increments per second with every counter on its own cache line.
+    pub padded_ops: f64,
+    /// One-way core-to-core latency in nanoseconds, zero on the diagonal.
+    pub latencies: Vec<Vec<f64>>,
+    /// Whether the ping-pong threads were pinned to their cores.
+    pub pinned: bool,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "coherency ... shared {:.0} ops/s, padded {:.0} ops/s ({:.2}x)",
+            self.shared_ops,
+            self.padded_ops,
+            match self.shared_ops {
+                0. => 0.,
+                _ => self.padded_ops / self.shared_ops,
+            }
+        )?;
+        write!(
+            f,
+            "\n  core-to-core latency (ns{}) ...",
+            if self.pinned { "" } else { ", unpinned" }
+        )?;
+        for (a, row) in self.latencies.iter().enumerate() {
+            write!(f, "\n  {a:>3}")?;
+            for (b, latency_ns) in row.iter().enumerate() {
+                match a == b {
+                    true => write!(f, " {:>6}", "-")?,
+                    false => write!(f, " {latency_ns:>6.1}")?,
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+struct Context {
+    num_threads: usize,
+    ops: usize,
+    rounds: usize,
+}
+
+impl Context {
+    fn new(features: &CpuFeatures, config: Config) -> Self {
+        Self {
+            num_threads: features.num_cores.max(1),
+            ops: config.ops,
+            rounds: config.rounds.max(1),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bench() {
+        let result = bench(
+            &CpuFeatures {
+                num_cores: 2,
+                ..Default::default()
+            },
+            Config {
+                ops: 10_000,
+                rounds: 1000,
+            },
+        );
+
+        assert!(result.is_ok(), "expected success");
+        let result = result.unwrap();
+        assert!(result.shared_ops > 0.);
+        assert!(result.padded_ops > 0.);
+        assert_eq!(2, result.latencies.len());
+        assert_eq!(0., result.latencies[0][0]);
+        assert!(result.latencies[0][1] > 0.);
+        assert_eq!(result.latencies[0][1], result.latencies[1][0]);
+
+        println!("{result}");
+    }
+
+    #[test]
+    fn test_counters() {
+        let padded = (0..3)
+            .map(|_| Padded(AtomicU64::new(0)))
+            .collect::<Vec<_>>();
+
+        assert!(run_counters(&padded, 100).is_ok());
+
+        let padded = (0..3)
+            .map(|i| Padded(AtomicU64::new((i == 1) as u64)))
+            .collect::<Vec<_>>();
+        assert!(matches!(
+            run_counters(&padded, 100),
+            Err(Error::InvalidCount(1, 101))
+        ));
+    }
+}
@@ -0,0 +1,323 @@
+use std::{
+    fmt,
+    hint::black_box,
+    mem,
+    time::{Duration, Instant},
+};
+
+use rand::Rng;
+
+use crate::utils::{Size, MB};
+
+const PATTERNS: [Pattern; 5] = [
+    Pattern::WalkingOnes,
+    Pattern::WalkingZeros,
+    Pattern::Checkerboard,
+    Pattern::Random,
+    Pattern::AddressInAddress,
+];
+
+pub(crate) fn bench(config: Config) -> Result<Report, Error> {
+    let mut context = Context::new(config);
+    let mut report_builder = ReportBuilder::new(context.data.len() * mem::size_of::<u64>());
+
+    for _ in 0..context.iters {
+        let seed = context.rng.gen();
+
+        for pattern in PATTERNS {
+            let start = Instant::now();
+            run_test(&mut context.data, pattern, seed)?;
+            report_builder.add(pattern, start.elapsed());
+        }
+    }
+
+    Ok(report_builder.build())
+}
+
+/// Writes and verifies every variant of `pattern`.
+fn run_test(data: &mut [u64], pattern: Pattern, seed: u64) -> Result<(), Error> {
+    for variant in 0..pattern.variants() {
+        fill(data, pattern, variant, seed);
+        // keep the writes from being folded into the verification
+        black_box(data.as_mut_ptr());
+        verify(data, pattern, variant, seed)?;
+    }
+
+    Ok(())
+}
+
+fn fill(data: &mut [u64], pattern: Pattern, variant: u32, seed: u64) {
+    for (i, x) in data.iter_mut().enumerate() {
+        *x = pattern.value(variant, seed, i, x as *const u64 as u64);
+    }
+}
+
+fn verify(data: &[u64], pattern: Pattern, variant: u32, seed: u64) -> Result<(), Error> {
+    for (i, x) in data.iter().enumerate() {
+        let expected = pattern.value(variant, seed, i, x as *const u64 as u64);
+        if *x != expected {
+            return Err(Error::InvalidValue(Failure {
+                pattern,
+                offset: i * mem::size_of::<u64>(),
+                expected,
+                actual: *x,
+            }));
+        }
+    }
+
+    Ok(())
+}
+
+/// Stateless generator for the random pattern, so that any word can be recomputed from its index.
+fn splitmix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9e3779b97f4a7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d049bb133111eb);
+
+    x ^ (x >> 31)
+}
+
+pub struct Config {
+    pub rng: Box<dyn rand::RngCore>,
+
+    pub data_len: usize,
+    pub iters: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            rng: Box::new(rand::thread_rng()),
+            data_len: 16 * MB,
+            iters: 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pattern {
+    /// A single set bit walking through all 64 positions.
+    WalkingOnes,
+    /// A single cleared bit walking through all 64 positions.
+    WalkingZeros,
+    /// Alternating `0x55..` and `0xaa..` words, then inverted.
+    Checkerboard,
+    /// Random words from a seed.
+    Random,
+    /// Every word holds its own address, then its complement.
+    AddressInAddress,
+}
+
+impl Pattern {
+    fn variants(&self) -> u32 {
+        match self {
+            Pattern::WalkingOnes | Pattern::WalkingZeros => u64::BITS,
+            Pattern::Checkerboard | Pattern::AddressInAddress => 2,
+            Pattern::Random => 1,
+        }
+    }
+
+    /// Expected value of the word at index `i` and address `addr`.
+    fn value(&self, variant: u32, seed: u64, i: usize, addr: u64) -> u64 {
+        const CHECKER: u64 = 0x5555_5555_5555_5555;
+
+        match self {
+            Pattern::WalkingOnes => 1 << variant,
+            Pattern::WalkingZeros => !(1 << variant),
+            Pattern::Checkerboard => match (i as u32 + variant) % 2 {
+                0 => CHECKER,
+                _ => !CHECKER,
+            },
+            Pattern::Random => splitmix64(seed.wrapping_add(i as u64)),
+            Pattern::AddressInAddress => match variant {
+                0 => addr,
+                _ => !addr,
+            },
+        }
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Pattern::WalkingOnes => write!(f, "walking ones"),
+            Pattern::WalkingZeros => write!(f, "walking zeros"),
+            Pattern::Checkerboard => write!(f, "checkerboard"),
+            Pattern::Random => write!(f, "random"),
+            Pattern::AddressInAddress => write!(f, "address in address"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Failure {
+    pub pattern: Pattern,
+    /// Offset of the failing word in bytes.
+    pub offset: usize,
+    pub expected: u64,
+    pub actual: u64,
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} failed at offset {:#x}, expected {:#018x}, got {:#018x}",
+            self.pattern, self.offset, self.expected, self.actual
+        )
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    InvalidValue(Failure),
+}
+
+pub struct PatternReport {
+    pub pattern: Pattern,
+    pub duration: Duration,
+    /// Bytes written and verified per second in GB/s.
+    pub gbs: f64,
+}
+
+pub struct Report {
+    pub data_len: usize,
+    pub patterns: Vec<PatternReport>,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "integrity ({}) ... passed", Size(self.data_len))?;
+        for pattern in self.patterns.iter() {
+            write!(f, ", {} {:.2} GB/s", pattern.pattern, pattern.gbs)?;
+        }
+
+        Ok(())
+    }
+}
+
+struct ReportBuilder {
+    data_len: usize,
+    durations: Vec<Duration>,
+    iters: Vec<u32>,
+}
+
+impl ReportBuilder {
+    fn new(data_len: usize) -> Self {
+        Self {
+            data_len,
+            durations: vec![Duration::ZERO; PATTERNS.len()],
+            iters: vec![0; PATTERNS.len()],
+        }
+    }
+
+    fn add(&mut self, pattern: Pattern, duration: Duration) {
+        let i = PATTERNS.iter().position(|&p| p == pattern).unwrap();
+        self.durations[i] += duration;
+        self.iters[i] += 1;
+    }
+
+    fn build(self) -> Report {
+        let data_len = self.data_len;
+
+        Report {
+            data_len,
+            patterns: PATTERNS
+                .into_iter()
+                .zip(self.durations.into_iter().zip(self.iters))
+                .map(|(pattern, (duration, iters))| {
+                    // every variant writes and reads the whole buffer
+                    let bytes = 2 * data_len as u64 * (iters * pattern.variants()) as u64;
+
+                    PatternReport {
+                        pattern,
+                        duration,
+                        gbs: match duration {
+                            Duration::ZERO => 0.,
+                            _ => bytes as f64 / duration.as_secs_f64() / 1e9,
+                        },
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+struct Context {
+    rng: Box<dyn rand::RngCore>,
+    iters: usize,
+    data: Vec<u64>,
+}
+
+impl Context {
+    fn new(config: Config) -> Self {
+        Self {
+            rng: config.rng,
+            iters: config.iters,
+            data: vec![0; (config.data_len / mem::size_of::<u64>()).max(1)],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bench() {
+        let result = bench(Config {
+            data_len: 64 * 1024,
+            iters: 2,
+            ..Default::default()
+        });
+
+        assert!(result.is_ok(), "expected success");
+        let result = result.unwrap();
+        assert_eq!(64 * 1024, result.data_len);
+        assert_eq!(PATTERNS.len(), result.patterns.len());
+        assert!(result.patterns.iter().all(|pattern| pattern.gbs > 0.));
+
+        println!("{result}");
+    }
+
+    #[test]
+    fn test_patterns() {
+        let mut data = vec![0u64; 4];
+        let addr = data.as_ptr() as u64;
+
+        fill(&mut data, Pattern::WalkingOnes, 3, 0);
+        assert_eq!(vec![8; 4], data);
+
+        fill(&mut data, Pattern::WalkingZeros, 0, 0);
+        assert_eq!(vec![u64::MAX - 1; 4], data);
+
+        fill(&mut data, Pattern::Checkerboard, 1, 0);
+        assert_eq!(0xaaaa_aaaa_aaaa_aaaa, data[0]);
+        assert_eq!(0x5555_5555_5555_5555, data[1]);
+
+        fill(&mut data, Pattern::AddressInAddress, 0, 0);
+        assert_eq!(vec![addr, addr + 8, addr + 16, addr + 24], data);
+    }
+
+    #[test]
+    fn test_failure() {
+        let mut data = vec![0u64; 16];
+
+        for pattern in PATTERNS {
+            for variant in 0..pattern.variants() {
+                fill(&mut data, pattern, variant, 42);
+                assert!(verify(&data, pattern, variant, 42).is_ok());
+
+                data[5] ^= 1 << 17;
+                match verify(&data, pattern, variant, 42) {
+                    Err(Error::InvalidValue(failure)) => {
+                        assert_eq!(pattern, failure.pattern);
+                        assert_eq!(40, failure.offset);
+                        assert_eq!(1 << 17, failure.expected ^ failure.actual);
+                    }
+                    Ok(_) => panic!("expected failure"),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,203 @@
+use std::{
+    fmt,
+    hint::black_box,
+    mem,
+    time::{Duration, Instant},
+};
+
+use rand::Rng;
+
+use crate::utils::{Size, MB};
+
+/// Distance between the chased pointers, so that every load touches a new cache line.
+pub(crate) const LINE_LEN: usize = 64;
+const LINE_STRIDE: usize = LINE_LEN / mem::size_of::<usize>();
+
+pub(crate) fn bench(config: Config) -> Result<Report, Error> {
+    let context = Context::new(config);
+    let mut report_builder = ReportBuilder::new(context.working_set);
+
+    let mut start: Instant;
+    for _ in 0..context.iters {
+        start = Instant::now();
+        let last = black_box(run_test(&context.chain, context.loads));
+        report_builder.add(start.elapsed(), context.loads);
+
+        // the chain is a single cycle and the loads cover it whole
+        if last != 0 {
+            return Err(Error::BrokenChain(last));
+        }
+    }
+
+    Ok(report_builder.build())
+}
+
+/// Builds a random cyclic permutation over the cache lines of `working_set` bytes,
+/// the first slot of each line holds the index of the next one.
+pub(crate) fn chain<R: Rng + ?Sized>(rng: &mut R, working_set: usize) -> Vec<usize> {
+    let lines = (working_set / LINE_LEN).max(1);
+
+    // Sattolo's algorithm, yields a single cycle through all lines
+    let mut next = (0..lines).collect::<Vec<_>>();
+    for i in (1..lines).rev() {
+        let j = rng.gen_range(0..i);
+        next.swap(i, j);
+    }
+
+    let mut chain = vec![0; lines * LINE_STRIDE];
+    for (line, next) in next.into_iter().enumerate() {
+        chain[line * LINE_STRIDE] = next * LINE_STRIDE;
+    }
+
+    chain
+}
+
+/// Follows `loads` pointers starting at the first line and returns the index it stopped at.
+pub(crate) fn run_test(chain: &[usize], loads: usize) -> usize {
+    let mut i = 0;
+    for _ in 0..loads {
+        i = chain[i];
+    }
+
+    i
+}
+
+pub struct Config {
+    pub rng: Box<dyn rand::RngCore>,
+
+    /// Size of the memory the pointers are chased through.
+    pub working_set: usize,
+    /// Minimum number of loads per iteration, rounded up to whole cycles through the working set.
+    pub loads: usize,
+    pub iters: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            rng: Box::new(rand::thread_rng()),
+            working_set: 64 * MB,
+            loads: 1 << 22,
+            iters: 5,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    BrokenChain(usize),
+}
+
+pub struct Report {
+    pub working_set: usize,
+    pub loads: u64,
+    /// Average load-to-use latency in nanoseconds.
+    pub avg_ns: f64,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "latency ({}) ... {:.2} ns",
+            Size(self.working_set),
+            self.avg_ns
+        )
+    }
+}
+
+struct ReportBuilder {
+    working_set: usize,
+    duration: Duration,
+    loads: u64,
+}
+
+impl ReportBuilder {
+    fn new(working_set: usize) -> Self {
+        Self {
+            working_set,
+            duration: Duration::ZERO,
+            loads: 0,
+        }
+    }
+
+    fn add(&mut self, duration: Duration, loads: usize) {
+        self.duration += duration;
+        self.loads += loads as u64;
+    }
+
+    fn build(self) -> Report {
+        Report {
+            working_set: self.working_set,
+            loads: self.loads,
+            avg_ns: match self.loads {
+                0 => 0.,
+                _ => self.duration.as_nanos() as f64 / self.loads as f64,
+            },
+        }
+    }
+}
+
+struct Context {
+    working_set: usize,
+    chain: Vec<usize>,
+    loads: usize,
+    iters: usize,
+}
+
+impl Context {
+    fn new(mut config: Config) -> Self {
+        let chain = chain(&mut config.rng, config.working_set);
+        let lines = chain.len() / LINE_STRIDE;
+
+        Self {
+            working_set: chain.len() * mem::size_of::<usize>(),
+            chain,
+            loads: config.loads.max(1).div_ceil(lines) * lines,
+            iters: config.iters,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::utils::KB;
+
+    #[test]
+    fn test_bench() {
+        let result = bench(Config {
+            working_set: 256 * KB,
+            loads: 100_000,
+            iters: 5,
+            ..Default::default()
+        });
+
+        assert!(result.is_ok(), "expected success");
+        let result = result.unwrap();
+        assert_eq!(256 * KB, result.working_set);
+        assert!(result.loads >= 500_000);
+        assert!(result.avg_ns > 0.);
+
+        println!("{result}");
+    }
+
+    #[test]
+    fn test_chain() {
+        let chain = chain(&mut rand::thread_rng(), 64 * KB);
+        let lines = 64 * KB / LINE_LEN;
+        assert_eq!(lines * LINE_STRIDE, chain.len());
+
+        // every line is visited exactly once before returning to the first one
+        let mut visited = vec![false; lines];
+        let mut i = 0;
+        for _ in 0..lines {
+            assert!(!visited[i / LINE_STRIDE]);
+            visited[i / LINE_STRIDE] = true;
+            i = chain[i];
+        }
+        assert_eq!(0, i);
+        assert!(visited.into_iter().all(|v| v));
+    }
+}
@@ -0,0 +1,364 @@
+use std::{
+    fmt,
+    hint::black_box,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use rand::Rng;
+
+use crate::{
+    utils::{Size, KB},
+    CpuFeatures,
+};
+
+/// Number of pre-generated allocation sizes, reused round robin.
+const SIZES: usize = 4096;
+/// Number of blocks handed to the next thread at once.
+const BATCH: usize = 64;
+
+pub(crate) fn bench(features: &CpuFeatures, config: Config) -> Result<Report, Error> {
+    let mut context = Context::new(config);
+    let num_threads = features.num_cores.max(1);
+    let mut report_builder = ReportBuilder::new(num_threads);
+    let rss_start = current_rss();
+    context.sample_rss();
+
+    let mut start: Instant;
+    for _ in 0..context.iters {
+        start = Instant::now();
+        let allocs = black_box(run_test(&mut context)?);
+        report_builder.add_single(start.elapsed(), allocs);
+    }
+
+    for _ in 0..context.iters {
+        start = Instant::now();
+        let allocs = black_box(run_test_multithread(&context, num_threads)?);
+        report_builder.add_multi(start.elapsed(), allocs);
+    }
+
+    let peak_rss_growth = rss_start.map_or(0, |start| {
+        context
+            .peak_rss
+            .load(Ordering::Relaxed)
+            .saturating_sub(start)
+    });
+
+    Ok(report_builder.build(peak_rss_growth))
+}
+
+/// Replaces blocks in random slots of the live set, every replacement frees one block and allocates another.
+fn run_test(context: &mut Context) -> Result<usize, Error> {
+    let sizes = &context.sizes;
+    let slots = &mut context.slots;
+
+    for i in 0..context.ops {
+        let (size, slot) = sizes[i % sizes.len()];
+        slots[slot] = allocate(size, i)?;
+    }
+    let allocs = context.ops;
+
+    context.sample_rss();
+    context
+        .slots
+        .iter_mut()
+        .for_each(|block| *block = Vec::new());
+
+    Ok(allocs)
+}
+
+/// Runs `num_threads` threads in a ring, each thread allocates batches of blocks
+/// and frees the batches allocated by its predecessor.
+fn run_test_multithread(context: &Context, num_threads: usize) -> Result<usize, Error> {
+    let batches = context.ops.div_ceil(num_threads * BATCH).max(1);
+    let (txs, rxs): (Vec<_>, Vec<_>) = (0..num_threads)
+        .map(|_| mpsc::channel::<Vec<Vec<u8>>>())
+        .unzip();
+
+    thread::scope(|scope| {
+        let handles = rxs
+            .into_iter()
+            .enumerate()
+            .map(|(t, rx)| {
+                let tx = txs[(t + 1) % num_threads].clone();
+                let sizes = &context.sizes;
+
+                scope.spawn(move || {
+                    let offset = t * sizes.len() / num_threads;
+                    let mut allocs = 0;
+                    for b in 0..batches {
+                        let mut batch = Vec::with_capacity(BATCH);
+                        for i in 0..BATCH {
+                            let op = b * BATCH + i;
+                            let (size, _) = sizes[(offset + op) % sizes.len()];
+                            batch.push(allocate(size, op)?);
+                        }
+                        allocs += BATCH;
+
+                        tx.send(batch).map_err(|_| Error::Disconnected)?;
+                        if t == 0 && b == batches / 2 {
+                            // every thread holds up to two batches at this point
+                            context.sample_rss();
+                        }
+                        drop(rx.recv().map_err(|_| Error::Disconnected)?);
+                    }
+
+                    Ok(allocs)
+                })
+            })
+            .collect::<Vec<_>>();
+        drop(txs);
+
+        handles.into_iter().try_fold(0, |acc, handle| {
+            handle
+                .join()
+                .map_err(|_| Error::Disconnected)?
+                .map(|allocs| acc + allocs)
+        })
+    })
+}
+
+/// Allocates a block of `size` bytes and touches its first byte.
+fn allocate(size: usize, op: usize) -> Result<Vec<u8>, Error> {
+    let mut block = Vec::with_capacity(size);
+    if block.capacity() < size {
+        return Err(Error::WrongCapacity(block.capacity()));
+    }
+    block.push(op as u8);
+
+    Ok(block)
+}
+
+/// Current resident set size of the process in bytes, `None` if not available.
+fn current_rss() -> Option<u64> {
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    {
+        // the second field of `statm` is the number of resident pages
+        let statm = std::fs::read_to_string("/proc/self/statm").ok()?;
+        let pages = statm.split_whitespace().nth(1)?.parse::<u64>().ok()?;
+
+        Some(pages * crate::utils::page_size() as u64)
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    None
+}
+
+pub struct Config {
+    pub rng: Box<dyn rand::RngCore>,
+
+    /// Allocations per iteration, split between the threads of the multithreaded test.
+    pub ops: usize,
+    /// Number of blocks kept alive by the single threaded test.
+    pub slots: usize,
+    pub iters: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            rng: Box::new(rand::thread_rng()),
+            ops: 1 << 20,
+            slots: 4096,
+            iters: 5,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    WrongCapacity(usize),
+    /// A thread of the multithreaded test stopped early.
+    Disconnected,
+}
+
+pub struct Throughput {
+    pub duration: Duration,
+    pub allocs: usize,
+    /// Allocations per second, every allocation is paired with a free.
+    pub aps: f64,
+}
+
+impl Throughput {
+    fn new(duration: Duration, allocs: usize) -> Self {
+        Self {
+            duration,
+            allocs,
+            aps: match duration {
+                Duration::ZERO => 0.,
+                _ => allocs as f64 / duration.as_secs_f64(),
+            },
+        }
+    }
+}
+
+pub struct Report {
+    pub num_threads: usize,
+    pub single: Throughput,
+    pub multi: Throughput,
+    /// Growth of the resident set size of the whole process in bytes, sampled while the live set
+    /// is largest, `0` if not available.
+    pub peak_rss_growth: u64,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "malloc ... {:.0} allocs/s, {} threads {:.0} allocs/s, peak RSS +{}",
+            self.single.aps,
+            self.num_threads,
+            self.multi.aps,
+            Size(self.peak_rss_growth as usize)
+        )
+    }
+}
+
+struct ReportBuilder {
+    num_threads: usize,
+    single: (Duration, usize),
+    multi: (Duration, usize),
+}
+
+impl ReportBuilder {
+    fn new(num_threads: usize) -> Self {
+        Self {
+            num_threads,
+            single: (Duration::ZERO, 0),
+            multi: (Duration::ZERO, 0),
+        }
+    }
+
+    fn add_single(&mut self, duration: Duration, allocs: usize) {
+        self.single.0 += duration;
+        self.single.1 += allocs;
+    }
+
+    fn add_multi(&mut self, duration: Duration, allocs: usize) {
+        self.multi.0 += duration;
+        self.multi.1 += allocs;
+    }
+
+    fn build(self, peak_rss_growth: u64) -> Report {
+        Report {
+            num_threads: self.num_threads,
+            single: Throughput::new(self.single.0, self.single.1),
+            multi: Throughput::new(self.multi.0, self.multi.1),
+            peak_rss_growth,
+        }
+    }
+}
+
+struct Context {
+    ops: usize,
+    iters: usize,
+
+    /// Pairs of allocation size and slot.
+    sizes: Vec<(usize, usize)>,
+    slots: Vec<Vec<u8>>,
+    /// Largest resident set size sampled so far, `0` if not available.
+    peak_rss: AtomicU64,
+}
+
+impl Context {
+    fn new(mut config: Config) -> Self {
+        let slots = config.slots.max(1);
+        let sizes = (0..SIZES)
+            .map(|_| {
+                // mostly small blocks with the occasional page or larger buffer
+                let size = match config.rng.gen_range(0..100) {
+                    0..90 => config.rng.gen_range(8..=256),
+                    90..99 => config.rng.gen_range(257..=4 * KB),
+                    _ => config.rng.gen_range(4 * KB + 1..=64 * KB),
+                };
+
+                (size, config.rng.gen_range(0..slots))
+            })
+            .collect();
+
+        Self {
+            ops: config.ops,
+            iters: config.iters,
+            sizes,
+            slots: vec![Vec::new(); slots],
+            peak_rss: AtomicU64::new(0),
+        }
+    }
+
+    fn sample_rss(&self) {
+        if let Some(rss) = current_rss() {
+            self.peak_rss.fetch_max(rss, Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bench() {
+        let result = bench(
+            &CpuFeatures {
+                num_cores: 4,
+                ..Default::default()
+            },
+            Config {
+                ops: 10_000,
+                slots: 256,
+                iters: 3,
+                ..Default::default()
+            },
+        );
+
+        assert!(result.is_ok(), "expected success");
+        let result = result.unwrap();
+        assert_eq!(30_000, result.single.allocs);
+        assert!(result.multi.allocs >= 30_000);
+        assert!(result.single.aps > 0.);
+        assert!(result.multi.aps > 0.);
+
+        println!("{result}");
+    }
+
+    #[test]
+    fn test_multithread() {
+        let context = Context::new(Config {
+            ops: 1000,
+            ..Default::default()
+        });
+
+        for num_threads in [1, 3] {
+            let allocs = run_test_multithread(&context, num_threads);
+
+            assert!(allocs.is_ok(), "expected success");
+            assert_eq!(
+                num_threads * 1000usize.div_ceil(num_threads * BATCH) * BATCH,
+                allocs.unwrap()
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    fn test_peak_rss() {
+        let mut context = Context::new(Config {
+            ops: 1000,
+            ..Default::default()
+        });
+
+        assert!(run_test(&mut context).is_ok(), "expected success");
+        let single_peak = context.peak_rss.load(Ordering::Relaxed);
+        assert!(single_peak > 0);
+
+        assert!(
+            run_test_multithread(&context, 3).is_ok(),
+            "expected success"
+        );
+        assert!(context.peak_rss.load(Ordering::Relaxed) >= single_peak);
+    }
+}
@@ -0,0 +1,358 @@
+use std::{
+    fmt, ptr,
+    time::{Duration, Instant},
+};
+
+use crate::utils::{last_os_error, page_size, Avg, Size, GB, MB};
+
+pub(crate) fn bench(config: Config) -> Result<Report, Error> {
+    let context = Context::new(config);
+    let mut report_builder = ReportBuilder::new(&context);
+
+    let mut start: Instant;
+    for _ in 0..context.iters {
+        start = Instant::now();
+        let mapping = Mapping::new(context.data_len)?;
+        report_builder.add_map(start.elapsed());
+
+        let faults = minor_faults();
+        start = Instant::now();
+        mapping.touch(context.page_size, 0)?;
+        report_builder.add_first_touch(start.elapsed(), minor_faults() - faults);
+
+        let faults = minor_faults();
+        start = Instant::now();
+        mapping.touch(context.page_size, 1)?;
+        report_builder.add_retouch(start.elapsed(), minor_faults() - faults);
+
+        start = Instant::now();
+        mapping.advise(libc::MADV_DONTNEED)?;
+        report_builder.add_reclaim(start.elapsed());
+
+        // private anonymous pages read back as zero once they are reclaimed,
+        // other systems may keep the contents until the pages are actually reused
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        mapping.verify_reclaimed(context.page_size)?;
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    if context.thp {
+        for _ in 0..context.iters {
+            let mapping = Mapping::new(context.data_len)?;
+            // fails if the kernel is built without transparent huge pages
+            if mapping.advise(libc::MADV_HUGEPAGE).is_err() {
+                break;
+            }
+
+            let faults = minor_faults();
+            start = Instant::now();
+            mapping.touch(context.page_size, 0)?;
+            report_builder.add_thp(start.elapsed(), minor_faults() - faults);
+        }
+    }
+
+    Ok(report_builder.build())
+}
+
+/// Private anonymous mapping, unmapped on drop.
+struct Mapping {
+    ptr: *mut u8,
+    len: usize,
+}
+
+impl Mapping {
+    fn new(len: usize) -> Result<Self, Error> {
+        let ptr = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+                -1,
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(Error::Mmap(last_os_error()));
+        }
+
+        Ok(Self {
+            ptr: ptr as *mut u8,
+            len,
+        })
+    }
+
+    fn advise(&self, advice: libc::c_int) -> Result<(), Error> {
+        if unsafe { libc::madvise(self.ptr as *mut libc::c_void, self.len, advice) } != 0 {
+            return Err(Error::Madvise(last_os_error()));
+        }
+
+        Ok(())
+    }
+
+    /// Increments the first byte of every page, expecting it to hold `expected`.
+    fn touch(&self, page_size: usize, expected: u8) -> Result<(), Error> {
+        for offset in (0..self.len).step_by(page_size) {
+            let byte = unsafe { self.ptr.add(offset) };
+            let value = unsafe { ptr::read_volatile(byte) };
+            if value != expected {
+                return Err(Error::InvalidValue(offset, value));
+            }
+            unsafe { ptr::write_volatile(byte, value.wrapping_add(1)) };
+        }
+
+        Ok(())
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    fn verify_reclaimed(&self, page_size: usize) -> Result<(), Error> {
+        for offset in (0..self.len).step_by(page_size) {
+            let value = unsafe { ptr::read_volatile(self.ptr.add(offset)) };
+            if value != 0 {
+                return Err(Error::InvalidValue(offset, value));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for Mapping {
+    fn drop(&mut self) {
+        unsafe { libc::munmap(self.ptr as *mut libc::c_void, self.len) };
+    }
+}
+
+/// Minor page faults of the calling thread so far, of the whole process where per-thread usage
+/// is not available.
+fn minor_faults() -> u64 {
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    let who = RUSAGE_THREAD;
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    let who = libc::RUSAGE_SELF;
+
+    let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+    if unsafe { libc::getrusage(who, &mut usage) } != 0 {
+        return 0;
+    }
+
+    usage.ru_minflt as u64
+}
+
+/// `RUSAGE_THREAD` of the Linux kernel, not exported by `libc` for Android.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+const RUSAGE_THREAD: libc::c_int = 1;
+
+pub struct Config {
+    /// Size of the mapping, rounded up to whole pages.
+    pub data_len: usize,
+    pub iters: usize,
+    /// Repeats the first touch with transparent huge pages requested, Linux and Android only.
+    pub thp: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            data_len: 256 * MB,
+            iters: 5,
+            thp: true,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    /// `errno` of the failed call.
+    Mmap(i32),
+    Madvise(i32),
+    /// Offset and value of the first unexpected byte.
+    InvalidValue(usize, u8),
+}
+
+pub struct Touch {
+    pub avg_t: Duration,
+    /// Average number of minor page faults.
+    pub faults: u64,
+    /// Page faults per second.
+    pub fps: f64,
+    /// Time per GiB touched.
+    pub gib_t: Duration,
+}
+
+pub struct Report {
+    pub data_len: usize,
+    pub page_size: usize,
+    pub map_avg_t: Duration,
+    /// Touching every page of a fresh mapping, allocation through page faults.
+    pub first_touch: Touch,
+    /// Touching every page again, memory access only.
+    pub retouch: Touch,
+    /// `madvise(MADV_DONTNEED)` of the whole mapping.
+    pub reclaim_avg_t: Duration,
+    /// First touch with transparent huge pages, `None` if not available.
+    pub thp: Option<Touch>,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn write_touch(f: &mut fmt::Formatter<'_>, name: &str, touch: &Touch) -> fmt::Result {
+            write!(
+                f,
+                ", {name} {:.0} faults/s {:.2} ms/GiB",
+                touch.fps,
+                touch.gib_t.as_secs_f64() * 1e3
+            )
+        }
+
+        write!(
+            f,
+            "mmap ({}) ... map {:.6} s",
+            Size(self.data_len),
+            self.map_avg_t.as_secs_f64()
+        )?;
+        write_touch(f, "first touch", &self.first_touch)?;
+        write_touch(f, "retouch", &self.retouch)?;
+        write!(f, ", reclaim {:.6} s", self.reclaim_avg_t.as_secs_f64())?;
+        if let Some(thp) = &self.thp {
+            write_touch(f, "thp", thp)?;
+        }
+
+        Ok(())
+    }
+}
+
+struct ReportBuilder {
+    data_len: usize,
+    page_size: usize,
+    map_ts: Vec<Duration>,
+    first_touch: Vec<(Duration, u64)>,
+    retouch: Vec<(Duration, u64)>,
+    reclaim_ts: Vec<Duration>,
+    thp: Vec<(Duration, u64)>,
+}
+
+impl ReportBuilder {
+    fn new(context: &Context) -> Self {
+        Self {
+            data_len: context.data_len,
+            page_size: context.page_size,
+            map_ts: Vec::with_capacity(context.iters),
+            first_touch: Vec::with_capacity(context.iters),
+            retouch: Vec::with_capacity(context.iters),
+            reclaim_ts: Vec::with_capacity(context.iters),
+            thp: Vec::new(),
+        }
+    }
+
+    fn add_map(&mut self, time: Duration) {
+        self.map_ts.push(time);
+    }
+
+    fn add_first_touch(&mut self, time: Duration, faults: u64) {
+        self.first_touch.push((time, faults));
+    }
+
+    fn add_retouch(&mut self, time: Duration, faults: u64) {
+        self.retouch.push((time, faults));
+    }
+
+    fn add_reclaim(&mut self, time: Duration) {
+        self.reclaim_ts.push(time);
+    }
+
+    #[cfg_attr(not(any(target_os = "linux", target_os = "android")), allow(dead_code))]
+    fn add_thp(&mut self, time: Duration, faults: u64) {
+        self.thp.push((time, faults));
+    }
+
+    fn build(self) -> Report {
+        let data_len = self.data_len;
+        let touch = |samples: Vec<(Duration, u64)>| {
+            let (ts, faults): (Vec<_>, Vec<_>) = samples.into_iter().unzip();
+            let avg_t = ts.avg();
+            let faults = faults.iter().sum::<u64>() / faults.len().max(1) as u64;
+
+            Touch {
+                avg_t,
+                faults,
+                fps: match avg_t {
+                    Duration::ZERO => 0.,
+                    _ => faults as f64 / avg_t.as_secs_f64(),
+                },
+                gib_t: avg_t.mul_f64(GB as f64 / data_len as f64),
+            }
+        };
+
+        Report {
+            data_len: self.data_len,
+            page_size: self.page_size,
+            map_avg_t: self.map_ts.avg(),
+            first_touch: touch(self.first_touch),
+            retouch: touch(self.retouch),
+            reclaim_avg_t: self.reclaim_ts.avg(),
+            thp: (!self.thp.is_empty()).then(|| touch(self.thp)),
+        }
+    }
+}
+
+struct Context {
+    data_len: usize,
+    page_size: usize,
+    iters: usize,
+    #[cfg_attr(not(any(target_os = "linux", target_os = "android")), allow(dead_code))]
+    thp: bool,
+}
+
+impl Context {
+    fn new(config: Config) -> Self {
+        let page_size = page_size();
+
+        Self {
+            data_len: config.data_len.max(1).next_multiple_of(page_size),
+            page_size,
+            iters: config.iters,
+            thp: config.thp,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bench() {
+        let result = bench(Config {
+            data_len: 16 * MB,
+            iters: 3,
+            ..Default::default()
+        });
+
+        assert!(result.is_ok(), "expected success");
+        let result = result.unwrap();
+        assert_eq!(16 * MB, result.data_len);
+        assert!(result.map_avg_t > Duration::ZERO);
+        assert!(result.first_touch.faults > 0);
+        assert!(result.first_touch.fps > 0.);
+        assert!(result.first_touch.gib_t > Duration::ZERO);
+        assert!(result.retouch.faults < result.first_touch.faults);
+
+        println!("{result}");
+    }
+
+    #[test]
+    fn test_mapping() {
+        let page_size = Context::new(Default::default()).page_size;
+        let mapping = Mapping::new(4 * page_size).unwrap();
+
+        assert!(mapping.touch(page_size, 0).is_ok());
+        assert!(matches!(
+            mapping.touch(page_size, 0),
+            Err(Error::InvalidValue(0, 1))
+        ));
+        assert!(mapping.advise(libc::MADV_DONTNEED).is_ok());
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        assert!(mapping.verify_reclaimed(page_size).is_ok());
+    }
+}
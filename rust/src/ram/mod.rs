@@ -4,17 +4,41 @@ use crate::utils::MB;
 
 pub(crate) mod access;
 pub(crate) mod alloc;
+pub(crate) mod coherency;
+pub(crate) mod integrity;
+pub(crate) mod latency;
+pub(crate) mod malloc;
+pub(crate) mod mmap;
+pub(crate) mod probe;
+pub(crate) mod stream;
+pub(crate) mod sweep;
 
 #[derive(Default)]
 pub struct Config {
     pub alloc: alloc::Config,
     pub access: access::Config,
+    pub latency: Option<latency::Config>,
+    pub sweep: Option<sweep::Config>,
+    pub stream: Option<stream::Config>,
+    pub malloc: Option<malloc::Config>,
+    pub mmap: Option<mmap::Config>,
+    pub probe: Option<probe::Config>,
+    pub integrity: Option<integrity::Config>,
+    pub coherency: Option<coherency::Config>,
 }
 
 pub struct Report {
     pub total_mem: u64,
     pub alloc: alloc::Report,
     pub access: access::Report,
+    pub latency: Option<latency::Report>,
+    pub sweep: Option<sweep::Report>,
+    pub stream: Option<stream::Report>,
+    pub malloc: Option<malloc::Report>,
+    pub mmap: Option<mmap::Report>,
+    pub probe: Option<probe::Report>,
+    pub integrity: Option<integrity::Report>,
+    pub coherency: Option<coherency::Report>,
 }
 
 impl fmt::Display for Report {
@@ -24,6 +48,30 @@ impl fmt::Display for Report {
         writeln!(f, "{i} total mem {} MB", self.total_mem / MB as u64)?;
         writeln!(f, "{i} {}", self.alloc)?;
         write!(f, "{i} {}", self.access)?;
+        if let Some(latency) = &self.latency {
+            write!(f, "\n{i} {latency}")?;
+        }
+        if let Some(sweep) = &self.sweep {
+            write!(f, "\n{i} {sweep}")?;
+        }
+        if let Some(stream) = &self.stream {
+            write!(f, "\n{i} {stream}")?;
+        }
+        if let Some(malloc) = &self.malloc {
+            write!(f, "\n{i} {malloc}")?;
+        }
+        if let Some(mmap) = &self.mmap {
+            write!(f, "\n{i} {mmap}")?;
+        }
+        if let Some(probe) = &self.probe {
+            write!(f, "\n{i} {probe}")?;
+        }
+        if let Some(integrity) = &self.integrity {
+            write!(f, "\n{i} {integrity}")?;
+        }
+        if let Some(coherency) = &self.coherency {
+            write!(f, "\n{i} {coherency}")?;
+        }
 
         Ok(())
     }
@@ -33,4 +81,12 @@ impl fmt::Display for Report {
 pub enum Error {
     Alloc(alloc::Error),
     Access(access::Error),
+    Latency(latency::Error),
+    Sweep(sweep::Error),
+    Stream(stream::Error),
+    Malloc(malloc::Error),
+    Mmap(mmap::Error),
+    Probe(probe::Error),
+    Integrity(integrity::Error),
+    Coherency(coherency::Error),
 }
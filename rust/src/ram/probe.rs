@@ -0,0 +1,382 @@
+use std::{
+    fmt, io, mem, ptr, str,
+    time::{Duration, Instant},
+};
+
+use crate::utils::{last_os_error, page_size, Size, GB, MB};
+
+/// Progress message of the child, total bytes touched and nanoseconds elapsed.
+const MSG_LEN: usize = 2 * mem::size_of::<u64>();
+
+/// Runs the probe in a forked child, so that running out of memory only takes down the child.
+///
+/// On Android the low memory killer (lmkd) neither protects nor targets the child: it only picks
+/// processes registered by the ActivityManager and kills the whole process group of the app,
+/// which includes the child. The probe is therefore bounded by the available memory minus a
+/// margin, and the child stops on its own once less than the margin is left.
+pub(crate) fn bench(config: Config, total_ram: u64) -> Result<Report, Error> {
+    let context = Context::new(config, total_ram, mem_available());
+
+    let mut fds = [0; 2];
+    if unsafe { libc::pipe(fds.as_mut_ptr()) } != 0 {
+        return Err(Error::Pipe(last_os_error()));
+    }
+    let [read_fd, write_fd] = fds;
+
+    match unsafe { libc::fork() } {
+        -1 => {
+            let errno = last_os_error();
+            unsafe {
+                libc::close(read_fd);
+                libc::close(write_fd);
+            }
+
+            Err(Error::Fork(errno))
+        }
+        0 => {
+            unsafe { libc::close(read_fd) };
+            run_child(&context, write_fd)
+        }
+        pid => {
+            unsafe { libc::close(write_fd) };
+            let progress = read_progress(read_fd);
+            unsafe { libc::close(read_fd) };
+
+            let status = wait(pid)?;
+            let (len, duration) = progress?;
+
+            Ok(Report::new(&context, len, duration, status))
+        }
+    }
+}
+
+/// Allocates and touches memory chunk by chunk until the limit, the margin or the first failure,
+/// reporting the progress after every chunk.
+///
+/// Runs in the forked child, so it only makes async-signal-safe calls and never returns.
+fn run_child(context: &Context, fd: libc::c_int) -> ! {
+    // volunteer as the first victim of the kernel OOM killer, lmkd ignores the child
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    unsafe {
+        let oom_fd = libc::open(c"/proc/self/oom_score_adj".as_ptr(), libc::O_WRONLY);
+        if oom_fd >= 0 {
+            libc::write(oom_fd, b"1000".as_ptr() as *const libc::c_void, 4);
+            libc::close(oom_fd);
+        }
+    }
+
+    let start = Instant::now();
+    let mut len = 0;
+    while len + context.chunk_len <= context.limit {
+        if mem_available().is_some_and(|avail| avail < (context.margin + context.chunk_len) as u64)
+        {
+            unsafe { libc::_exit(EXIT_MARGIN) };
+        }
+
+        let chunk = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                context.chunk_len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+                -1,
+                0,
+            )
+        };
+        if chunk == libc::MAP_FAILED {
+            unsafe { libc::_exit(EXIT_REFUSED) };
+        }
+
+        let chunk = chunk as *mut u8;
+        for offset in (0..context.chunk_len).step_by(context.page_size) {
+            unsafe { ptr::write_volatile(chunk.add(offset), 1) };
+        }
+        len += context.chunk_len;
+
+        let mut msg = [0u8; MSG_LEN];
+        msg[..8].copy_from_slice(&(len as u64).to_le_bytes());
+        msg[8..].copy_from_slice(&(start.elapsed().as_nanos() as u64).to_le_bytes());
+        if unsafe { libc::write(fd, msg.as_ptr() as *const libc::c_void, MSG_LEN) }
+            != MSG_LEN as isize
+        {
+            unsafe { libc::_exit(EXIT_PIPE) };
+        }
+    }
+
+    unsafe { libc::_exit(0) }
+}
+
+const EXIT_REFUSED: libc::c_int = 2;
+const EXIT_PIPE: libc::c_int = 3;
+const EXIT_MARGIN: libc::c_int = 4;
+
+/// Available memory in bytes as estimated by the kernel, `None` if not available.
+///
+/// Called in the forked child as well, so it reads into a stack buffer and doesn't allocate.
+fn mem_available() -> Option<u64> {
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    {
+        let mut buf = [0u8; 512];
+        let len = unsafe {
+            let fd = libc::open(c"/proc/meminfo".as_ptr(), libc::O_RDONLY);
+            if fd < 0 {
+                return None;
+            }
+            let len = libc::read(fd, buf.as_mut_ptr() as *mut libc::c_void, buf.len());
+            libc::close(fd);
+
+            len
+        };
+
+        parse_mem_available(&buf[..usize::try_from(len).ok()?])
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    None
+}
+
+/// Parses the `MemAvailable` line of `/proc/meminfo`, given in kB.
+fn parse_mem_available(meminfo: &[u8]) -> Option<u64> {
+    const KEY: &[u8] = b"MemAvailable:";
+
+    let start = meminfo
+        .windows(KEY.len())
+        .position(|window| window == KEY)?
+        + KEY.len();
+    let value = meminfo[start..].trim_ascii_start();
+    let digits = value
+        .iter()
+        .take_while(|byte| byte.is_ascii_digit())
+        .count();
+    let kb = str::from_utf8(&value[..digits]).ok()?.parse::<u64>().ok()?;
+
+    kb.checked_mul(1024)
+}
+
+/// Reads the progress messages until the child exits, returns the last one.
+fn read_progress(fd: libc::c_int) -> Result<(usize, Duration), Error> {
+    let mut progress = (0, Duration::ZERO);
+
+    let mut msg = [0u8; MSG_LEN];
+    let mut filled = 0;
+    loop {
+        let n = unsafe {
+            libc::read(
+                fd,
+                msg[filled..].as_mut_ptr() as *mut libc::c_void,
+                MSG_LEN - filled,
+            )
+        };
+        match n {
+            0 => break,
+            n if n < 0 => {
+                if io::Error::last_os_error().kind() == io::ErrorKind::Interrupted {
+                    continue;
+                }
+                return Err(Error::Pipe(last_os_error()));
+            }
+            n => filled += n as usize,
+        }
+
+        if filled == MSG_LEN {
+            let len = u64::from_le_bytes(msg[..8].try_into().unwrap());
+            let nanos = u64::from_le_bytes(msg[8..].try_into().unwrap());
+            progress = (len as usize, Duration::from_nanos(nanos));
+            filled = 0;
+        }
+    }
+
+    Ok(progress)
+}
+
+fn wait(pid: libc::pid_t) -> Result<Status, Error> {
+    let mut status = 0;
+    loop {
+        if unsafe { libc::waitpid(pid, &mut status, 0) } == pid {
+            break;
+        }
+        if io::Error::last_os_error().kind() != io::ErrorKind::Interrupted {
+            return Err(Error::Wait(last_os_error()));
+        }
+    }
+
+    Ok(if libc::WIFSIGNALED(status) {
+        Status::Killed(libc::WTERMSIG(status))
+    } else {
+        match libc::WEXITSTATUS(status) {
+            0 => Status::Limit,
+            EXIT_MARGIN => Status::Margin,
+            EXIT_REFUSED => Status::Refused,
+            code => Status::Failed(code),
+        }
+    })
+}
+
+pub struct Config {
+    /// Upper bound of the probe, further limited to half of the total RAM, to the available
+    /// memory minus the margin and to the address space of the target.
+    pub limit: u64,
+    /// Memory left available to the rest of the system, the child stops before going below it.
+    pub margin: usize,
+    /// Size of each allocation.
+    pub chunk_len: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            limit: 4 * GB as u64,
+            margin: 512 * MB,
+            chunk_len: 64 * MB,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    /// `errno` of the failed call.
+    Pipe(i32),
+    Fork(i32),
+    Wait(i32),
+}
+
+/// How the probing child process ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// Reached the safety limit.
+    Limit,
+    /// Stopped with less than the margin of memory available.
+    Margin,
+    /// The OS refused to map more memory.
+    Refused,
+    /// Killed by a signal, usually by the (low) memory killer.
+    Killed(i32),
+    /// Exited early with the given code.
+    Failed(i32),
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Limit => write!(f, "limit"),
+            Status::Margin => write!(f, "margin"),
+            Status::Refused => write!(f, "refused"),
+            Status::Killed(signal) => write!(f, "killed by signal {signal}"),
+            Status::Failed(code) => write!(f, "failed with code {code}"),
+        }
+    }
+}
+
+pub struct Report {
+    pub limit: usize,
+    /// Largest size allocated and touched successfully.
+    pub max_len: usize,
+    pub duration: Duration,
+    /// Allocation throughput in GB/s.
+    pub gbs: f64,
+    pub status: Status,
+}
+
+impl Report {
+    fn new(context: &Context, max_len: usize, duration: Duration, status: Status) -> Self {
+        Self {
+            limit: context.limit,
+            max_len,
+            duration,
+            gbs: match duration {
+                Duration::ZERO => 0.,
+                _ => max_len as f64 / duration.as_secs_f64() / 1e9,
+            },
+            status,
+        }
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "memory probe ... {} of {} ({}), {:.2} GB/s",
+            Size(self.max_len),
+            Size(self.limit),
+            self.status,
+            self.gbs
+        )
+    }
+}
+
+struct Context {
+    limit: usize,
+    margin: usize,
+    chunk_len: usize,
+    page_size: usize,
+}
+
+impl Context {
+    fn new(config: Config, total_ram: u64, mem_available: Option<u64>) -> Self {
+        let page_size = page_size();
+        let mut limit = match total_ram {
+            0 => config.limit,
+            _ => config.limit.min(total_ram / 2),
+        };
+        if let Some(mem_available) = mem_available {
+            limit = limit.min(mem_available.saturating_sub(config.margin as u64));
+        }
+
+        Self {
+            limit: usize::try_from(limit).unwrap_or(usize::MAX),
+            margin: config.margin,
+            chunk_len: config.chunk_len.max(1).next_multiple_of(page_size),
+            page_size,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bench() {
+        let result = bench(
+            Config {
+                limit: 64 * MB as u64,
+                margin: 0,
+                chunk_len: 8 * MB,
+            },
+            0,
+        );
+
+        assert!(result.is_ok(), "expected success");
+        let result = result.unwrap();
+        assert_eq!(Status::Limit, result.status);
+        assert_eq!(64 * MB, result.max_len);
+        assert!(result.gbs > 0.);
+
+        println!("{result}");
+    }
+
+    #[test]
+    fn test_limit_bounded() {
+        let context = Context::new(Default::default(), 2 * GB as u64, None);
+
+        assert_eq!(GB, context.limit);
+    }
+
+    #[test]
+    fn test_limit_bounded_by_available() {
+        let context = Context::new(Default::default(), 16 * GB as u64, Some(GB as u64));
+        assert_eq!(GB - 512 * MB, context.limit);
+
+        let context = Context::new(Default::default(), 16 * GB as u64, Some(256 * MB as u64));
+        assert_eq!(0, context.limit);
+    }
+
+    #[test]
+    fn test_parse_mem_available() {
+        let meminfo = b"MemTotal:       16384000 kB\nMemFree:         1024000 kB\nMemAvailable:    8192000 kB\n";
+
+        assert_eq!(Some(8192000 * 1024), parse_mem_available(meminfo));
+        assert_eq!(None, parse_mem_available(b"MemTotal:       16384000 kB\n"));
+    }
+}
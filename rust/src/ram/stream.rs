@@ -0,0 +1,412 @@
+use std::{
+    fmt,
+    hint::black_box,
+    mem,
+    ops::{Add, Mul},
+    time::{Duration, Instant},
+};
+
+use rayon::{iter::IndexedParallelIterator, iter::ParallelIterator, slice::ParallelSliceMut};
+
+use crate::{utils::Size, CpuFeatures};
+
+const KERNELS: [Kernel; 6] = [
+    Kernel::Copy,
+    Kernel::Scale,
+    Kernel::Add,
+    Kernel::Triad,
+    Kernel::CopyFromSlice,
+    Kernel::Fill,
+];
+
+pub(crate) fn bench(features: &CpuFeatures, config: Config) -> Result<Report, Error> {
+    match config.element_type {
+        ElementType::U64 => bench_typed::<u64>(features, config),
+        ElementType::F64 => bench_typed::<f64>(features, config),
+    }
+}
+
+fn bench_typed<T: Element>(features: &CpuFeatures, config: Config) -> Result<Report, Error> {
+    let mut context = Context::<T>::new(config);
+    let threadpool = rayon::ThreadPoolBuilder::new()
+        .num_threads(features.num_cores)
+        .build()
+        .unwrap();
+    let mut report_builder = ReportBuilder::new(
+        context.element_type,
+        context.a.len() * mem::size_of::<T>(),
+        features.num_cores,
+    );
+
+    for threadpool in [None, Some(&threadpool)] {
+        for _ in 0..context.iters {
+            context.reset_arrays();
+
+            for kernel in KERNELS {
+                let start = Instant::now();
+                run_test(kernel, &mut context, threadpool);
+                report_builder.add(
+                    kernel,
+                    threadpool.is_some(),
+                    start.elapsed(),
+                    kernel.arrays() * context.a.len() * mem::size_of::<T>(),
+                );
+            }
+
+            context.verify_arrays()?;
+        }
+    }
+
+    Ok(report_builder.build())
+}
+
+fn run_test<T: Element>(
+    kernel: Kernel,
+    context: &mut Context<T>,
+    threadpool: Option<&rayon::ThreadPool>,
+) {
+    let (a, b, c) = (&mut context.a, &mut context.b, &mut context.c);
+
+    match threadpool {
+        None => run_kernel(kernel, a, b, c),
+        Some(threadpool) => {
+            let chunk_len = a.len().div_ceil(threadpool.current_num_threads()).max(1);
+
+            threadpool.install(|| {
+                a.par_chunks_mut(chunk_len)
+                    .zip(b.par_chunks_mut(chunk_len))
+                    .zip(c.par_chunks_mut(chunk_len))
+                    .for_each(|((a, b), c)| run_kernel(kernel, a, b, c))
+            })
+        }
+    }
+
+    black_box((a, b, c));
+}
+
+/// Runs `kernel` over `a`, `b` and `c` of the same length, each kernel uses the arrays it needs.
+fn run_kernel<T: Element>(kernel: Kernel, a: &mut [T], b: &mut [T], c: &mut [T]) {
+    let q = T::SCALAR;
+
+    match kernel {
+        Kernel::Copy => c.iter_mut().zip(a.iter()).for_each(|(c, &a)| *c = a),
+        Kernel::Scale => b.iter_mut().zip(c.iter()).for_each(|(b, &c)| *b = c * q),
+        Kernel::Add => c
+            .iter_mut()
+            .zip(a.iter().zip(b.iter()))
+            .for_each(|(c, (&a, &b))| *c = a + b),
+        Kernel::Triad => a
+            .iter_mut()
+            .zip(b.iter().zip(c.iter()))
+            .for_each(|(a, (&b, &c))| *a = b + c * q),
+        Kernel::CopyFromSlice => b.copy_from_slice(a),
+        Kernel::Fill => c.fill(T::from_u8(FILL)),
+    }
+}
+
+const FILL: u8 = 7;
+
+/// Element of the STREAM arrays, `u64` arithmetic wraps.
+trait Element: Copy + PartialEq + Send + Sync + Add<Output = Self> + Mul<Output = Self> {
+    const SCALAR: Self;
+
+    fn from_u8(value: u8) -> Self;
+}
+
+impl Element for u64 {
+    const SCALAR: Self = 3;
+
+    fn from_u8(value: u8) -> Self {
+        value as u64
+    }
+}
+
+impl Element for f64 {
+    const SCALAR: Self = 3.;
+
+    fn from_u8(value: u8) -> Self {
+        value as f64
+    }
+}
+
+pub struct Config {
+    pub element_type: ElementType,
+    /// Number of elements of each of the three arrays.
+    pub data_len: usize,
+    pub iters: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            element_type: ElementType::default(),
+            data_len: 4 * 1024 * 1024,
+            iters: 10,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ElementType {
+    U64,
+    #[default]
+    F64,
+}
+
+impl fmt::Display for ElementType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ElementType::U64 => write!(f, "u64"),
+            ElementType::F64 => write!(f, "f64"),
+        }
+    }
+}
+
+/// STREAM kernels followed by the standard library's bulk operations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kernel {
+    /// `c = a`
+    Copy,
+    /// `b = q * c`
+    Scale,
+    /// `c = a + b`
+    Add,
+    /// `a = b + q * c`
+    Triad,
+    /// `b.copy_from_slice(a)`
+    CopyFromSlice,
+    /// `c.fill(x)`
+    Fill,
+}
+
+impl Kernel {
+    /// Number of arrays read or written, every element counts once per array.
+    fn arrays(&self) -> usize {
+        match self {
+            Kernel::Copy | Kernel::Scale | Kernel::CopyFromSlice => 2,
+            Kernel::Add | Kernel::Triad => 3,
+            Kernel::Fill => 1,
+        }
+    }
+}
+
+impl fmt::Display for Kernel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Kernel::Copy => write!(f, "copy"),
+            Kernel::Scale => write!(f, "scale"),
+            Kernel::Add => write!(f, "add"),
+            Kernel::Triad => write!(f, "triad"),
+            Kernel::CopyFromSlice => write!(f, "copy_from_slice"),
+            Kernel::Fill => write!(f, "fill"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    /// Array and index of the first unexpected element.
+    InvalidValue(char, usize),
+}
+
+pub struct Bandwidth {
+    pub kernel: Kernel,
+    pub duration: Duration,
+    pub bytes: u64,
+    /// Bandwidth in GB/s.
+    pub gbs: f64,
+}
+
+pub struct Report {
+    pub element_type: ElementType,
+    /// Size of each array.
+    pub array_size: usize,
+    pub num_threads: usize,
+    pub single: Vec<Bandwidth>,
+    pub multi: Vec<Bandwidth>,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn write_bandwidths(f: &mut fmt::Formatter<'_>, bandwidths: &[Bandwidth]) -> fmt::Result {
+            for (i, bandwidth) in bandwidths.iter().enumerate() {
+                let sep = if i == 0 { "" } else { "," };
+                write!(f, "{sep} {} {:.2} GB/s", bandwidth.kernel, bandwidth.gbs)?;
+            }
+
+            Ok(())
+        }
+
+        write!(
+            f,
+            "stream ({}, {}) ...",
+            self.element_type,
+            Size(self.array_size)
+        )?;
+        write_bandwidths(f, &self.single)?;
+        write!(f, "\n  {} threads ...", self.num_threads)?;
+        write_bandwidths(f, &self.multi)?;
+
+        Ok(())
+    }
+}
+
+struct ReportBuilder {
+    element_type: ElementType,
+    array_size: usize,
+    num_threads: usize,
+    single: Vec<(Duration, u64)>,
+    multi: Vec<(Duration, u64)>,
+}
+
+impl ReportBuilder {
+    fn new(element_type: ElementType, array_size: usize, num_threads: usize) -> Self {
+        Self {
+            element_type,
+            array_size,
+            num_threads,
+            single: vec![(Duration::ZERO, 0); KERNELS.len()],
+            multi: vec![(Duration::ZERO, 0); KERNELS.len()],
+        }
+    }
+
+    fn add(&mut self, kernel: Kernel, multithread: bool, duration: Duration, bytes: usize) {
+        let i = KERNELS.iter().position(|&k| k == kernel).unwrap();
+        let (total_duration, total_bytes) = match multithread {
+            false => &mut self.single[i],
+            true => &mut self.multi[i],
+        };
+
+        *total_duration += duration;
+        *total_bytes += bytes as u64;
+    }
+
+    fn build(self) -> Report {
+        let bandwidths = |totals: Vec<(Duration, u64)>| {
+            KERNELS
+                .into_iter()
+                .zip(totals)
+                .map(|(kernel, (duration, bytes))| Bandwidth {
+                    kernel,
+                    duration,
+                    bytes,
+                    gbs: match duration {
+                        Duration::ZERO => 0.,
+                        _ => bytes as f64 / duration.as_secs_f64() / 1e9,
+                    },
+                })
+                .collect()
+        };
+
+        Report {
+            element_type: self.element_type,
+            array_size: self.array_size,
+            num_threads: self.num_threads,
+            single: bandwidths(self.single),
+            multi: bandwidths(self.multi),
+        }
+    }
+}
+
+struct Context<T> {
+    element_type: ElementType,
+    iters: usize,
+
+    a: Vec<T>,
+    b: Vec<T>,
+    c: Vec<T>,
+}
+
+impl<T: Element> Context<T> {
+    fn new(config: Config) -> Self {
+        let data_len = config.data_len.max(1);
+
+        Self {
+            element_type: config.element_type,
+            iters: config.iters,
+            a: vec![T::from_u8(1); data_len],
+            b: vec![T::from_u8(2); data_len],
+            c: vec![T::from_u8(0); data_len],
+        }
+    }
+
+    fn reset_arrays(&mut self) {
+        self.a.fill(T::from_u8(1));
+        self.b.fill(T::from_u8(2));
+        self.c.fill(T::from_u8(0));
+    }
+
+    /// Checks the arrays after all kernels ran once on the reset arrays:
+    /// `c = 1`, `b = 3`, `c = 4`, `a = 15`, `b = 15`, `c = 7`.
+    fn verify_arrays(&self) -> Result<(), Error> {
+        for (name, array, expected) in [
+            ('a', &self.a, T::from_u8(15)),
+            ('b', &self.b, T::from_u8(15)),
+            ('c', &self.c, T::from_u8(FILL)),
+        ] {
+            if let Some(i) = array.iter().position(|&x| x != expected) {
+                return Err(Error::InvalidValue(name, i));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bench() {
+        for element_type in [ElementType::U64, ElementType::F64] {
+            let result = bench(
+                &CpuFeatures {
+                    num_cores: 4,
+                    ..Default::default()
+                },
+                Config {
+                    element_type,
+                    data_len: 100_000,
+                    iters: 5,
+                },
+            );
+
+            assert!(result.is_ok(), "expected success");
+            let result = result.unwrap();
+            assert_eq!(800_000, result.array_size);
+            assert_eq!(KERNELS.len(), result.single.len());
+            assert!(result.single.iter().all(|bandwidth| bandwidth.gbs > 0.));
+            assert!(result.multi.iter().all(|bandwidth| bandwidth.gbs > 0.));
+
+            println!("{result}");
+        }
+    }
+
+    #[test]
+    fn test_kernels() {
+        let mut context = Context::<u64>::new(Config {
+            data_len: 10,
+            ..Default::default()
+        });
+        let threadpool = rayon::ThreadPoolBuilder::new()
+            .num_threads(3)
+            .build()
+            .unwrap();
+
+        for threadpool in [None, Some(&threadpool)] {
+            context.reset_arrays();
+            for kernel in &KERNELS[..4] {
+                run_test(*kernel, &mut context, threadpool);
+            }
+            assert_eq!(vec![15; 10], context.a);
+            assert_eq!(vec![3; 10], context.b);
+            assert_eq!(vec![4; 10], context.c);
+
+            for kernel in &KERNELS[4..] {
+                run_test(*kernel, &mut context, threadpool);
+            }
+            assert!(context.verify_arrays().is_ok());
+        }
+    }
+}
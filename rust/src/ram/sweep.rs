@@ -0,0 +1,335 @@
+use std::{
+    fmt,
+    hint::black_box,
+    mem,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    ram::latency,
+    utils::{Size, KB, MB},
+};
+
+/// Minimum latency increase from one level of the memory hierarchy to the next.
+const LEVEL_RATIO: f64 = 1.5;
+/// Latency increase between neighbouring points still in the transition to the next level.
+const RISING_RATIO: f64 = 1.15;
+
+pub(crate) fn bench(config: Config, total_ram: u64) -> Result<Report, Error> {
+    let mut context = Context::new(config, total_ram);
+    let mut report_builder = ReportBuilder::new(context.working_sets.len());
+
+    for working_set in context.working_sets.clone() {
+        let latency_ns = run_latency(&mut context, working_set);
+        let bandwidth_gbs = run_bandwidth(&context, working_set)?;
+
+        report_builder.add(Point {
+            working_set,
+            latency_ns,
+            bandwidth_gbs,
+        });
+    }
+
+    Ok(report_builder.build())
+}
+
+fn run_latency(context: &mut Context, working_set: usize) -> f64 {
+    let chain = latency::chain(&mut context.rng, working_set);
+    let lines = chain.len() * mem::size_of::<usize>() / latency::LINE_LEN;
+
+    // warm up the caches the working set fits in
+    black_box(latency::run_test(&chain, lines.min(context.loads)));
+
+    let start = Instant::now();
+    black_box(latency::run_test(&chain, context.loads));
+
+    start.elapsed().as_nanos() as f64 / context.loads as f64
+}
+
+fn run_bandwidth(context: &Context, working_set: usize) -> Result<f64, Error> {
+    let data = vec![1u64; (working_set / mem::size_of::<u64>()).max(1)];
+    let passes = context.bytes.div_ceil(working_set).max(1);
+
+    black_box(read(&data));
+
+    let start = Instant::now();
+    for _ in 0..passes {
+        let sum = black_box(read(&data));
+        if sum != data.len() as u64 {
+            return Err(Error::InvalidSum(sum));
+        }
+    }
+    let elapsed = start.elapsed();
+
+    let bytes = (passes * data.len() * mem::size_of::<u64>()) as f64;
+
+    Ok(match elapsed {
+        Duration::ZERO => 0.,
+        _ => bytes / elapsed.as_secs_f64() / 1e9,
+    })
+}
+
+fn read(data: &[u64]) -> u64 {
+    data.iter().fold(0, |acc, &x| acc.wrapping_add(x))
+}
+
+/// Splits the latency curve into plateaus, each plateau is a level of the memory hierarchy.
+/// The last level is assumed to be DRAM.
+fn levels(points: &[Point]) -> Vec<Level> {
+    let mut ranges = Vec::new();
+
+    let mut start = 0;
+    let mut i = 1;
+    while i < points.len() {
+        if points[i].latency_ns > points[start].latency_ns * LEVEL_RATIO {
+            ranges.push(start..i);
+
+            // skip the transition to the next plateau
+            while i + 1 < points.len()
+                && points[i + 1].latency_ns > points[i].latency_ns * RISING_RATIO
+            {
+                i += 1;
+            }
+            start = i;
+        }
+
+        i += 1;
+    }
+    if !points.is_empty() {
+        ranges.push(start..points.len());
+    }
+
+    let n = ranges.len();
+    ranges
+        .into_iter()
+        .enumerate()
+        .map(|(level, range)| {
+            let mut latencies = points[range.clone()]
+                .iter()
+                .map(|point| point.latency_ns)
+                .collect::<Vec<_>>();
+            latencies.sort_by(f64::total_cmp);
+
+            let is_dram = n > 1 && level == n - 1;
+            Level {
+                name: match is_dram {
+                    true => String::from("DRAM"),
+                    false => format!("L{}", level + 1),
+                },
+                size: (!is_dram).then(|| points[range.end - 1].working_set),
+                latency_ns: latencies[latencies.len() / 2],
+            }
+        })
+        .collect()
+}
+
+pub struct Config {
+    pub rng: Box<dyn rand::RngCore>,
+
+    pub min_working_set: usize,
+    /// Upper bound of the sweep, further limited to a quarter of the total RAM.
+    pub max_working_set: usize,
+    /// Number of loads per latency measurement.
+    pub loads: usize,
+    /// Minimum number of bytes read per bandwidth measurement.
+    pub bytes: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            rng: Box::new(rand::thread_rng()),
+            min_working_set: 4 * KB,
+            max_working_set: 512 * MB,
+            loads: 1 << 20,
+            bytes: 256 * MB,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    InvalidSum(u64),
+}
+
+pub struct Point {
+    pub working_set: usize,
+    pub latency_ns: f64,
+    pub bandwidth_gbs: f64,
+}
+
+pub struct Level {
+    /// `L1`, `L2`, ... or `DRAM`
+    pub name: String,
+    /// Largest working set that fits into the level, `None` for DRAM.
+    pub size: Option<usize>,
+    pub latency_ns: f64,
+}
+
+pub struct Report {
+    pub points: Vec<Point>,
+    pub levels: Vec<Level>,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cache sweep ...")?;
+        for (i, level) in self.levels.iter().enumerate() {
+            let sep = if i == 0 { "" } else { "," };
+            match level.size {
+                Some(size) => write!(
+                    f,
+                    "{sep} {} {} {:.2} ns",
+                    level.name,
+                    Size(size),
+                    level.latency_ns
+                )?,
+                None => write!(f, "{sep} {} {:.2} ns", level.name, level.latency_ns)?,
+            }
+        }
+        for point in self.points.iter() {
+            write!(
+                f,
+                "\n  {} ... {:.2} ns, {:.2} GB/s",
+                Size(point.working_set),
+                point.latency_ns,
+                point.bandwidth_gbs
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+struct ReportBuilder {
+    points: Vec<Point>,
+}
+
+impl ReportBuilder {
+    fn new(points: usize) -> Self {
+        Self {
+            points: Vec::with_capacity(points),
+        }
+    }
+
+    fn add(&mut self, point: Point) {
+        self.points.push(point);
+    }
+
+    fn build(self) -> Report {
+        Report {
+            levels: levels(&self.points),
+            points: self.points,
+        }
+    }
+}
+
+struct Context {
+    rng: Box<dyn rand::RngCore>,
+
+    /// Powers of two and the midpoints between them.
+    working_sets: Vec<usize>,
+    loads: usize,
+    bytes: usize,
+}
+
+impl Context {
+    fn new(config: Config, total_ram: u64) -> Self {
+        let max = match total_ram {
+            0 => config.max_working_set,
+            _ => config
+                .max_working_set
+                .min(usize::try_from(total_ram / 4).unwrap_or(usize::MAX)),
+        };
+
+        let mut working_sets = Vec::new();
+        let mut working_set = config.min_working_set.max(latency::LINE_LEN);
+        while working_set <= max {
+            working_sets.push(working_set);
+            if working_set * 3 / 2 <= max {
+                working_sets.push(working_set * 3 / 2);
+            }
+            working_set *= 2;
+        }
+
+        Self {
+            rng: config.rng,
+            working_sets,
+            loads: config.loads.max(1),
+            bytes: config.bytes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bench() {
+        let result = bench(
+            Config {
+                max_working_set: 256 * KB,
+                loads: 10_000,
+                bytes: MB,
+                ..Default::default()
+            },
+            0,
+        );
+
+        assert!(result.is_ok(), "expected success");
+        let result = result.unwrap();
+        assert_eq!(13, result.points.len());
+        assert_eq!(4 * KB, result.points[0].working_set);
+        assert_eq!(256 * KB, result.points[12].working_set);
+        assert!(result.points.iter().all(|point| point.latency_ns > 0.));
+        assert!(result.points.iter().all(|point| point.bandwidth_gbs > 0.));
+        assert!(!result.levels.is_empty());
+
+        println!("{result}");
+    }
+
+    #[test]
+    fn test_working_sets_bounded() {
+        let context = Context::new(Default::default(), 256 * MB as u64);
+
+        assert_eq!(Some(&(64 * MB)), context.working_sets.last());
+    }
+
+    #[test]
+    fn test_levels() {
+        let latencies = [
+            1., 1., 1., 1.1, 4., 4.2, 4.1, 12., 18., 20., 20., 90., 100., 100.,
+        ];
+        let points = latencies
+            .into_iter()
+            .enumerate()
+            .map(|(i, latency_ns)| Point {
+                working_set: (4 * KB) << i,
+                latency_ns,
+                bandwidth_gbs: 0.,
+            })
+            .collect::<Vec<_>>();
+
+        let levels = levels(&points);
+
+        assert_eq!(
+            vec!["L1", "L2", "L3", "DRAM"],
+            levels
+                .iter()
+                .map(|level| &level.name[..])
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![Some(32 * KB), Some(256 * KB), Some(4 * MB), None],
+            levels.iter().map(|level| level.size).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![1., 4.1, 20., 100.],
+            levels
+                .iter()
+                .map(|level| level.latency_ns)
+                .collect::<Vec<_>>()
+        );
+    }
+}
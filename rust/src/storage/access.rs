@@ -295,8 +295,7 @@ mod tests {
         let result = bench(
             &CpuFeatures {
                 num_cores: 8,
-                sve: false,
-                i8mm: false,
+                ..Default::default()
             },
             Config {
                 data_len_mb: 10,
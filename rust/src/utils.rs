@@ -1,4 +1,5 @@
 use std::{
+    fmt, io,
     ops::Add,
     slice,
     time::{Duration, Instant},
@@ -19,41 +20,60 @@ pub(crate) const KB: usize = 1024;
 pub(crate) const MB: usize = KB * KB;
 pub(crate) const GB: usize = KB * MB;
 
-pub(crate) fn slice_from_ptr_mut<'a, T>(ptr: *mut T, from: usize, until: usize) -> &'a mut [T] {
-    unsafe { slice::from_raw_parts_mut(ptr.add(from), until - from) }
+/// Size in bytes, displayed in the largest unit it reaches.
+pub(crate) struct Size(pub(crate) usize);
+
+impl fmt::Display for Size {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (unit, name) = match self.0 {
+            n if n >= GB => (GB, "GB"),
+            n if n >= MB => (MB, "MB"),
+            n if n >= KB => (KB, "KB"),
+            _ => (1, "B"),
+        };
+
+        match self.0 % unit {
+            0 => write!(f, "{} {name}", self.0 / unit),
+            _ => write!(f, "{:.1} {name}", self.0 as f64 / unit as f64),
+        }
+    }
 }
 
-pub(crate) fn is_pow(n: usize, pow: usize) -> bool {
-    let mut n = n as f64;
-    let pow = pow as f64;
-
-    while n > 1f64 {
-        n /= pow;
-        if n.fract() != 0.0 {
-            return false;
-        }
+/// Size of a memory page in bytes, 4 KB if it cannot be queried.
+pub(crate) fn page_size() -> usize {
+    match unsafe { libc::sysconf(libc::_SC_PAGESIZE) } {
+        page_size if page_size > 0 => page_size as usize,
+        _ => 4096,
     }
+}
 
-    true
+/// `errno` of the last failed OS call, `0` if not set.
+pub(crate) fn last_os_error() -> i32 {
+    io::Error::last_os_error()
+        .raw_os_error()
+        .unwrap_or_default()
 }
 
-pub(crate) fn closest_pow(n: usize, pow: usize) -> usize {
-    if n == 0 {
-        return 0;
-    }
+pub(crate) fn slice_from_ptr_mut<'a, T>(ptr: *mut T, from: usize, until: usize) -> &'a mut [T] {
+    unsafe { slice::from_raw_parts_mut(ptr.add(from), until - from) }
+}
 
-    let mut upper = 1;
-    while upper < n {
-        upper *= pow;
-    }
+/// `a + b + carry`, returns the result and the carry.
+pub(crate) fn adc(a: u64, b: u64, carry: u64) -> (u64, u64) {
+    let t = a as u128 + b as u128 + carry as u128;
+    (t as u64, (t >> 64) as u64)
+}
 
-    let lower = upper / pow;
+/// `a - b - borrow`, returns the result and the borrow.
+pub(crate) fn sbb(a: u64, b: u64, borrow: u64) -> (u64, u64) {
+    let t = (a as u128).wrapping_sub(b as u128 + borrow as u128);
+    (t as u64, (t >> 127) as u64)
+}
 
-    if n - lower < upper - n {
-        lower
-    } else {
-        upper
-    }
+/// `a + b * c + carry`, returns the result and the carry.
+pub(crate) fn mac(a: u64, b: u64, c: u64, carry: u64) -> (u64, u64) {
+    let t = a as u128 + b as u128 * c as u128 + carry as u128;
+    (t as u64, (t >> 64) as u64)
 }
 
 pub(crate) trait GetValue {
@@ -173,22 +193,10 @@ mod tests {
     }
 
     #[test]
-    fn test_is_pow() {
-        assert_eq!(true, is_pow(1, 2));
-        assert_eq!(true, is_pow(2, 2));
-        assert_eq!(true, is_pow(64, 2));
-        assert_eq!(true, is_pow(256, 2));
-
-        assert_eq!(false, is_pow(3, 2));
-        assert_eq!(false, is_pow(10, 2));
-        assert_eq!(false, is_pow(100, 2));
-    }
-
-    #[test]
-    fn test_closest_pow() {
-        assert_eq!(0, closest_pow(0, 2));
-        assert_eq!(1, closest_pow(1, 2));
-        assert_eq!(8, closest_pow(6, 2));
-        assert_eq!(32, closest_pow(47, 2));
+    fn test_size() {
+        assert_eq!("512 B", Size(512).to_string());
+        assert_eq!("4 KB", Size(4 * KB).to_string());
+        assert_eq!("1.5 MB", Size(3 * MB / 2).to_string());
+        assert_eq!("2 GB", Size(2 * GB).to_string());
     }
 }
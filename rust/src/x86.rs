@@ -0,0 +1,22 @@
+use std::arch::is_x86_feature_detected;
+
+use crate::{Bench, CpuFeatures};
+
+impl Bench {
+    pub fn with_cpuid(total_ram: u64, avail_storage: u64) -> Self {
+        Self::with_features(
+            total_ram,
+            avail_storage,
+            CpuFeatures {
+                num_cores: num_cpus::get(),
+                sse41: is_x86_feature_detected!("sse4.1"),
+                avx2: is_x86_feature_detected!("avx2"),
+                aes: is_x86_feature_detected!("aes"),
+                pmull: is_x86_feature_detected!("pclmulqdq"),
+                sha1: is_x86_feature_detected!("sha"),
+                sha2: is_x86_feature_detected!("sha"),
+                ..Default::default()
+            },
+        )
+    }
+}